@@ -1,33 +1,103 @@
+mod packed;
 mod pascal_str;
 mod pascal_string;
+mod pattern;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod split;
 
-pub use self::pascal_str::{Chars, CharsMut, Lines, PascalStr};
-pub use self::pascal_string::{IntoChars, PascalString, PascalStringAppendError, PascalStringCreateError, AsciiError};
+pub use self::packed::{PackedPascalStrings, PackedPascalStringsWriter};
+pub use self::pascal_str::{Chars, CharsMut, InteriorNullError, LineEnding, Lines, PascalStr, PrefixedBytesError};
+pub use self::pascal_string::{Drain, IntoChars, PascalString, PascalStringAppendError, PascalStringCreateError};
+pub use self::pascal_string::AsciiError;
+pub use self::pattern::{MatchIndices, Matches, Pattern};
+pub use self::split::{RSplit, Split, SplitN, SplitTerminator, SplitWhitespace};
+pub use self::pascal_string::{PascalString15, PascalString31, PascalString63, PascalString255};
+
+/// Create an `ascii::PascalString` from a string literal at compile time.
+///
+/// The capacity of the string is taken from the type the macro is used as, so this can be used to fill
+/// `const` and `static` tables. Compilation fails if the literal is longer than the capacity of the
+/// string, or if it is not valid ascii.
+///
+/// ```
+/// use pascal_string::ascii::{pstr, PascalString15};
+///
+/// static COMMANDS: [PascalString15; 2] = [pstr!("open"), pstr!("close")];
+/// assert_eq!(COMMANDS[1], "close");
+/// ```
+///
+/// ```compile_fail
+/// use pascal_string::ascii::{pstr, PascalString15};
+///
+/// static BAD: PascalString15 = pstr!("señor");
+/// ```
+#[doc(inline)]
+pub use __ascii_pstr as pstr;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ascii_pstr {
+    ($s:expr) => {
+        const { $crate::ascii::PascalString::from_literal($s) }
+    }
+}
+
+/// Create an `ascii::PascalString` from formatted text, like `format!`, without allocating.
+///
+/// Returns a `Result<PascalString, PascalStringAppendError>`, which is an `Err` if the formatted text is
+/// longer than the capacity of the string, or is not valid ascii. If the arguments are prefixed with
+/// `truncate;`, the text is truncated to the capacity of the string instead.
+///
+/// ```
+/// use pascal_string::ascii::{pformat, PascalString15, PascalStringAppendError};
+///
+/// let string: PascalString15 = pformat!("{}-{}", "hello", 42).unwrap();
+/// assert_eq!(string, "hello-42");
+/// assert_eq!(pformat!("{:>20}", 42) as Result<PascalString15, _>,
+///            Err(PascalStringAppendError::NoRoom { required: 20, available: 15 }));
+///
+/// let truncated: PascalString15 = pformat!(truncate; "{:>20}", 42).unwrap();
+/// assert_eq!(truncated.len(), 15);
+/// ```
+#[doc(inline)]
+pub use __ascii_pformat as pformat;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ascii_pformat {
+    (truncate; $($arg:tt)*) => {
+        $crate::ascii::PascalString::format_truncated(format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => {
+        $crate::ascii::PascalString::format(format_args!($($arg)*))
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use ascii_crate::*;
+    #[cfg(feature = "alloc")]
     use std::borrow::Cow;
+    #[cfg(feature = "alloc")]
     use std::ffi::{CStr, CString};
     use std::iter::IntoIterator;
+    use std::prelude::v1::*;
 
     #[test]
     fn test_string_creation() {
         let test = "Hello, my world!".to_owned();
-        let test_pascal = PascalString::from(&test).unwrap();
+        let test_pascal: PascalString = PascalString::from(&test).unwrap();
         assert_eq!(&test, test_pascal.as_str());
 
         let too_many_bytes = vec![12u8; 256];
-        assert!(match PascalString::from(&too_many_bytes) {
-            Err(PascalStringCreateError::InputTooLong) => true,
-            _ => false
-        });
+        assert!(matches!(PascalString255::from(&too_many_bytes), Err(PascalStringCreateError::InputTooLong { .. })));
     }
 
     #[test]
     fn test_character_append() {
-        let mut string = PascalString::new();
+        let mut string: PascalString = PascalString::new();
         assert!(string.try_push('h').is_ok());
         string.push('e');
         string.push(76u8);
@@ -41,7 +111,7 @@ mod tests {
 
     #[test]
     fn test_string_append() {
-        let mut string = PascalString::new();
+        let mut string: PascalString = PascalString::new();
         string.push_str("Hola, ");
         string.push_str("Senor!");
         assert_eq!(string, "Hola, Senor!");
@@ -50,12 +120,12 @@ mod tests {
     #[test]
     fn test_string_indexing_and_char_iteration() {
         {
-            let string = PascalString::from("q").unwrap();
+            let string: PascalString = PascalString::from("q").unwrap();
             assert_eq!(string[0u8], AsciiChar::q);
         }
 
         {
-            let string2 = PascalString::from("WASD").unwrap();
+            let string2: PascalString = PascalString::from("WASD").unwrap();
             {
                 let mut chars_iter = string2.chars();
                 assert_eq!(chars_iter.next(), Some(&AsciiChar::W));
@@ -77,53 +147,603 @@ mod tests {
     #[test]
     fn test_lines_iteration() {
         let string = "hello\n,\nmy\ndarling".to_string();
-        let pstring = PascalString::from(&string).unwrap();
+        let pstring: PascalString = PascalString::from(&string).unwrap();
         for (s0, s1) in pstring.lines().zip(string.split_whitespace()) {
             assert_eq!(s0.as_str(), s1);
         }
+        assert_eq!(pstring.lines().len(), 4);
+        assert_eq!(pstring.lines().next_back().map(|s| s.as_str()), Some("darling"));
+
+        for &source in &["", "\n", "a", "a\nb", "a\nb\n", "a\r\nb\r\n", "\n\na\n\n", "a\rb\r\n", "a\r"] {
+            let pstring = PascalString15::from(source).unwrap();
+            let expected: Vec<_> = source.lines().collect();
+            let forward: Vec<_> = pstring.lines().map(|s| s.as_str()).collect();
+            assert_eq!(forward, expected);
+            let mut backward: Vec<_> = pstring.lines().rev().map(|s| s.as_str()).collect();
+            backward.reverse();
+            assert_eq!(backward, expected);
+            assert_eq!(pstring.lines().len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_lines_with() {
+        let pstring = PascalString31::from("one\rtwo\r\nthree\n\rfour\r").unwrap();
+        let collect = |ending| pstring.lines_with(ending).map(|s| s.as_str()).collect::<Vec<_>>();
+        assert_eq!(collect(LineEnding::Cr), ["one", "two", "\nthree\n", "four"]);
+        assert_eq!(collect(LineEnding::Lf), ["one\rtwo\r", "three", "\rfour\r"]);
+        assert_eq!(collect(LineEnding::CrLf), ["one\rtwo", "three\n\rfour\r"]);
+        assert_eq!(collect(LineEnding::Any), ["one", "two", "three", "", "four"]);
+        let backward: Vec<_> = pstring.lines_with(LineEnding::Any).rev().map(|s| s.as_str()).collect();
+        assert_eq!(backward, ["four", "", "three", "two", "one"]);
+
+        let mut lines = pstring.lines_with(LineEnding::Any);
+        assert_eq!(lines.next().map(|s| s.as_str()), Some("one"));
+        assert_eq!(lines.next_back().map(|s| s.as_str()), Some("four"));
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_lossy_construction() {
+        let (string, loss) = PascalString::<4>::from_truncated("caption").unwrap();
+        assert_eq!(string, "capt");
+        assert_eq!(loss.truncated(), 3);
+        assert!(!loss.is_lossless());
+        let (_, loss) = PascalString::<4>::from_truncated("cap").unwrap();
+        assert!(loss.is_lossless());
+        assert!(PascalString::<4>::from_truncated("cañón").is_err());
+        assert!(PascalString::<2>::from_truncated("caño").is_ok());
+
+        let (string, loss) = PascalString15::from_lossy("señor", AsciiChar::Question);
+        assert_eq!(string, "se??or");
+        assert_eq!((loss.replaced(), loss.truncated()), (2, 0));
+        let (string, loss) = PascalString::<3>::from_lossy(b"\xffabc", AsciiChar::UnderScore);
+        assert_eq!(string, "_ab");
+        assert_eq!((loss.replaced(), loss.truncated()), (1, 1));
+
+        let (string, loss) = PascalString15::from_transliterated("Café Müller", AsciiChar::Question);
+        assert_eq!(string, "Cafe Muller");
+        assert!(loss.is_lossless());
+        let (string, loss) = PascalString::<8>::from_transliterated("Straße → Ø", AsciiChar::Question);
+        assert_eq!(string, "Strasse ");
+        assert_eq!((loss.replaced(), loss.truncated()), (0, "→ Ø".len()));
+        let (string, loss) = PascalString15::from_transliterated("½ Æsir", AsciiChar::Question);
+        assert_eq!(string, "? AEsir");
+        assert_eq!((loss.replaced(), loss.truncated()), (1, 0));
+    }
+
+    #[test]
+    fn test_trim_and_pad() {
+        let record = PascalString15::from("  NAME\t \r\n").unwrap();
+        assert_eq!(record.trim().as_str(), "NAME");
+        assert_eq!(record.trim_start().as_str(), "NAME\t \r\n");
+        assert_eq!(record.trim_end().as_str(), "  NAME");
+        let zeros = PascalString15::from("000120").unwrap();
+        assert_eq!(zeros.trim_start_matches('0').as_str(), "120");
+        assert_eq!(zeros.trim_end_matches('0').as_str(), "00012");
+        assert_eq!(zeros.trim_matches("00").as_str(), "0120");
+        assert_eq!(zeros.trim_matches(&['0', '1', '2']).as_str(), "");
+
+        let mut field = PascalString::<8>::from("42").unwrap();
+        field.pad_left(5, AsciiChar::_0).unwrap();
+        assert_eq!(field, "00042");
+        field.pad_right(8, AsciiChar::Space).unwrap();
+        assert_eq!(field, "00042   ");
+        field.pad_right(3, AsciiChar::Space).unwrap();
+        assert_eq!(field, "00042   ");
+        assert_eq!(field.trim_end().as_str(), "00042");
+
+        let mut title = PascalString::<8>::from("ab").unwrap();
+        assert_eq!(title.center(9, AsciiChar::Asterisk),
+                   Err(PascalStringAppendError::NoRoom { required: 7, available: 6 }));
+        assert_eq!(title, "ab");
+        title.center(7, AsciiChar::Asterisk).unwrap();
+        assert_eq!(title, "**ab***");
+        #[cfg(feature = "alloc")]
+        assert_eq!(title.as_cstr().unwrap().to_bytes(), b"**ab***");
+    }
+
+    #[test]
+    fn test_replace() {
+        let ident = PascalString15::from("my-long-name").unwrap();
+        let replaced: PascalString15 = ident.replace('-', "_").unwrap();
+        assert_eq!(replaced, "my_long_name");
+        let replaced: PascalString15 = ident.replacen("-", "", 1).unwrap();
+        assert_eq!(replaced, "mylong-name");
+        let replaced: PascalString<20, ::prefix::U16BE> = ident.replace(&['-', 'a'][..], "--").unwrap();
+        assert_eq!(replaced, "my--long--n--me");
+
+        let result: Result<PascalString15, _> = ident.replace("-", "___");
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 4, available: 3 }));
+        let truncated: PascalString15 = ident.replace_truncated("-", "___").unwrap();
+        assert_eq!(truncated, "my___long___nam");
+        let truncated: PascalString15 = ident.replacen_truncated("-", "___", 1).unwrap();
+        assert_eq!(truncated, "my___long-name");
+        let result: Result<PascalString15, _> = ident.replace("-", "ñ");
+        assert!(matches!(result, Err(PascalStringAppendError::NotValidAscii(_))));
+
+        let mut string = PascalString15::from("a.b.c").unwrap();
+        string.replace_in_place('.', "::").unwrap();
+        assert_eq!(string, "a::b::c");
+        assert_eq!(string.replace_in_place(':', "...."),
+                   Err(PascalStringAppendError::NoRoom { required: 4, available: 1 }));
+        assert_eq!(string, "a::b::c");
+    }
+
+    #[test]
+    fn test_editing() {
+        let mut string = PascalString15::from("hello world").unwrap();
+        let world = string.split_off(6);
+        assert_eq!(string, "hello ");
+        assert_eq!(world, "world");
+        string.truncate(4);
+        string.truncate(10);
+        assert_eq!(string, "hell");
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), b"hell");
+
+        string.insert_str("o, w", 4);
+        string.insert_str("H", 0);
+        assert_eq!(string, "Hhello, w");
+        string.replace_range(..2, "J");
+        assert_eq!(string, "Jello, w");
+        string.replace_range(7.., "world!");
+        assert_eq!(string, "Jello, world!");
+        assert_eq!(string.try_insert_str("!!!", 13),
+                   Err(PascalStringAppendError::NoRoom { required: 3, available: 2 }));
+        assert!(string.try_replace_range(0..1, "ñ").is_err());
+        assert_eq!(string, "Jello, world!");
+
+        string.retain(|ch| ch != AsciiChar::l);
+        assert_eq!(string, "Jeo, word!");
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), b"Jeo, word!");
+
+        {
+            let mut drain = string.drain(3..=4);
+            assert_eq!(drain.len(), 2);
+            assert_eq!(drain.next_back(), Some(AsciiChar::Space));
+        }
+        assert_eq!(string, "Jeoword!");
+        let drained: String = string.drain(..3).map(|ch| ch.as_char()).collect();
+        assert_eq!(drained, "Jeo");
+        assert_eq!(string, "word!");
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), b"word!");
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
     fn test_as_cstr() {
         {
             let msg = "I am your favourite cookie monster >:-)\0";
-            let pstr = PascalString::from(&msg).unwrap();
+            let pstr: PascalString = PascalString::from(msg).unwrap();
             let cstr = CStr::from_bytes_with_nul(msg.as_bytes()).unwrap();
             let pstr_as_cstr = pstr.as_cstr().unwrap();
-            assert!(match pstr_as_cstr {
-                Cow::Borrowed(_) => true,
-                _ => false
-            });
+            assert!(matches!(pstr_as_cstr, Cow::Borrowed(_)));
             assert_eq!(&*pstr_as_cstr, cstr);
         }
 
         {
             let oversized = ['l'; 255];
-            let string_oversized = {
-                let mut s = String::new();
-                for i in 0..oversized.len() {
-                    s.push(oversized[i]);
-                }
-                s
-            };
-            let pstr_oversized = PascalString::from_fixed_ascii_array(255, oversized).unwrap();
+            let string_oversized: String = oversized.iter().collect();
+            let pstr_oversized: PascalString = PascalString::from_fixed_ascii_array(255, oversized).unwrap();
             let cstr_from_pstr_oversized = pstr_oversized.as_cstr().unwrap();
             let cstr_from_string_oversized = CString::new(string_oversized).unwrap();
-            assert!(match cstr_from_pstr_oversized {
-                Cow::Owned(_) => true,
-                _ => false
-            });
+            assert!(matches!(cstr_from_pstr_oversized, Cow::Owned(_)));
             assert_eq!(cstr_from_pstr_oversized.into_owned(), cstr_from_string_oversized);
         }
 
         {
             let has_interior_null = "lol\0hi";
-            let pstr = PascalString::from(&has_interior_null).unwrap();
-            let err = match pstr.as_cstr() {
-                Err(err) => err,
-                _ => panic!("incorrect result")
-            };
+            let pstr: PascalString = PascalString::from(has_interior_null).unwrap();
+            let err = pstr.as_cstr().unwrap_err();
             assert_eq!(err.interior_null_index(), 3);
         }
     }
+
+    #[test]
+    fn test_custom_capacity() {
+        let mut string = PascalString15::new();
+        assert_eq!(string.capacity(), 15);
+        assert_eq!(::std::mem::size_of::<PascalString15>(), 16);
+        string.push_str("Hello, world!");
+        string.push('!');
+        string.push('!');
+        assert!(string.is_full());
+        assert_eq!(string.try_push('!'), Err(PascalStringAppendError::NoRoom { required: 1, available: 0 }));
+        assert_eq!(string.try_push_str("!"), Err(PascalStringAppendError::NoRoom { required: 1, available: 0 }));
+        assert!(matches!(PascalString15::from("Hello, world!!!!"), Err(PascalStringCreateError::InputTooLong { .. })));
+
+        assert_eq!(string.pop(), Some(AsciiChar::Exclamation));
+        assert_eq!(string.remove(5), AsciiChar::Comma);
+        string.insert(AsciiChar::Semicolon, 5);
+        string.insert('?', 14);
+        assert_eq!(string, "Hello; world!!?");
+
+        let array: [u8; 16] = string.to_array();
+        assert_eq!(array[0], 15);
+        assert_eq!(&array[1..], b"Hello; world!!?");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_custom_capacity_as_cstr() {
+        let string = PascalString31::from("hello").unwrap();
+        let cstr = string.as_cstr().unwrap();
+        assert!(matches!(cstr, Cow::Borrowed(_)));
+        assert_eq!(&*cstr, CStr::from_bytes_with_nul(b"hello\0").unwrap());
+
+        let full = PascalString15::from("fifteen letters").unwrap();
+        assert!(matches!(full.as_cstr().unwrap(), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_wide_length_prefix() {
+        use prefix::{U16BE, U16LE};
+
+        let mut string = PascalString::<300, U16BE>::new();
+        for _ in 0..30 {
+            string.push_str("0123456789");
+        }
+        assert!(string.is_full());
+        assert_eq!(string.len(), 300);
+        assert_eq!(string.try_push('a'), Err(PascalStringAppendError::NoRoom { required: 1, available: 0 }));
+        assert_eq!(string.remove(0), AsciiChar::_0);
+        assert_eq!(string.pop(), Some(AsciiChar::_9));
+
+        let array: [u8; 302] = string.clone().to_array();
+        assert_eq!(&array[..2], &[0x01, 0x2a]);
+        assert_eq!(&array[2..12], b"1234567890");
+
+        let little_endian = PascalString::<300, U16LE>::from(string.as_str()).unwrap();
+        let array: [u8; 302] = little_endian.to_array();
+        assert_eq!(&array[..2], &[0x2a, 0x01]);
+    }
+
+    #[test]
+    fn test_wide_string_past_255() {
+        use prefix::U16LE;
+
+        let mut string = PascalString::<400, U16LE>::new();
+        for _ in 0..30 {
+            string.push_str("abcdefghij");
+        }
+        string.insert('Z', 299);
+        assert_eq!(string.len(), 301);
+        assert_eq!(string.remove(299), AsciiChar::Z);
+        assert_eq!(string.remove(299), AsciiChar::j);
+
+        #[cfg(feature = "alloc")]
+        {
+            let owned = (*string).to_owned();
+            assert_eq!(owned.len(), 299);
+            assert_eq!(owned.as_str(), string.as_str());
+        }
+        #[cfg(feature = "std")]
+        #[allow(deprecated)]
+        {
+            use std::ascii::AsciiExt;
+            let upper = AsciiExt::to_ascii_uppercase(&*string);
+            assert_eq!(upper.len(), 299);
+            assert!(upper.as_str().starts_with("ABCDEFGHIJ"));
+        }
+    }
+
+    #[test]
+    fn test_from_prefixed_bytes() {
+        use prefix::U16BE;
+
+        let buf = b"\x05Hello\x06world!\x00";
+        let (hello, rest) = PascalStr::from_prefixed_bytes(buf).unwrap();
+        assert_eq!(hello, "Hello");
+        assert_eq!(hello.as_ptr() as *const u8, buf[1..].as_ptr());
+        let (world, rest) = PascalStr::from_prefixed_bytes(rest).unwrap();
+        assert_eq!(world, "world!");
+        let (empty, rest) = PascalStr::from_prefixed_bytes(rest).unwrap();
+        assert!(empty.is_empty());
+        assert!(rest.is_empty());
+
+        assert_eq!(PascalStr::from_prefixed_bytes(rest),
+                   Err(PrefixedBytesError::Truncated { required: 1, available: 0 }));
+        assert_eq!(PascalStr::from_prefixed_bytes(b"\x06short"),
+                   Err(PrefixedBytesError::Truncated { required: 7, available: 6 }));
+        assert!(matches!(PascalStr::from_prefixed_bytes(b"\x02\xffa"), Err(PrefixedBytesError::NotValidAscii(_))));
+
+        let (wide, rest) = PascalStr::from_prefixed_bytes_with::<U16BE>(b"\x00\x03abcd").unwrap();
+        assert_eq!(wide, "abc");
+        assert_eq!(rest, b"d");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_packed_strings() {
+        use prefix::{U16BE, U16LE};
+
+        // A Mac `STR#` resource: a big endian u16 count, followed by the strings.
+        let str_list = b"\x00\x02\x05Hello\x06world!trailing";
+        let mut strings = PackedPascalStrings::new(str_list).read_count::<U16BE>().unwrap();
+        assert_eq!(strings.next(), Some(Ok(From::from(AsciiStr::from_ascii("Hello").unwrap()))));
+        assert_eq!(strings.next().unwrap().unwrap(), "world!");
+        assert_eq!(strings.next(), None);
+        assert_eq!(strings.remainder(), b"trailing");
+
+        // A DNS name, which is terminated by an empty label.
+        let dns_name = b"\x03www\x07example\x03com\x00\x00\x01";
+        let mut labels = PackedPascalStrings::new(dns_name).until_empty();
+        let collected: Vec<&str> = labels.by_ref().map(|label| label.unwrap().as_str()).collect();
+        assert_eq!(collected, ["www", "example", "com"]);
+        assert_eq!(labels.remainder(), b"\x00\x01");
+
+        let mut truncated = PackedPascalStrings::new(b"\x02ab\x05abc");
+        assert!(truncated.next().unwrap().is_ok());
+        assert_eq!(truncated.next(), Some(Err(PrefixedBytesError::Truncated { required: 6, available: 4 })));
+        assert_eq!(truncated.next(), None);
+
+        let mut vec = b"header".to_vec();
+        {
+            let mut writer = PackedPascalStringsWriter::new(&mut vec);
+            writer.push(PascalString255::from("www").unwrap()).unwrap();
+            writer.push(PascalString15::from("example").unwrap()).unwrap();
+            writer.push_terminator().unwrap();
+            assert_eq!(writer.written(), 13);
+        }
+        assert_eq!(&vec[..], b"header\x03www\x07example\x00");
+
+        let mut buf = [0xffu8; 8];
+        {
+            let mut writer = PackedPascalStringsWriter::new(&mut buf[..]).with_prefix::<U16LE>();
+            writer.push(PascalString15::from("abc").unwrap()).unwrap();
+            assert_eq!(writer.push(PascalString15::from("ab").unwrap()),
+                       Err(PascalStringAppendError::NoRoom { required: 4, available: 3 }));
+            writer.push_terminator().unwrap();
+        }
+        assert_eq!(buf, [3, 0, b'a', b'b', b'c', 0, 0, 0xff]);
+        let read: Vec<_> = PackedPascalStrings::new(&buf[..7]).with_prefix::<U16LE>().until_empty().collect();
+        assert_eq!(read.len(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_and_write() {
+        use std::io::{Cursor, ErrorKind};
+        use prefix::U16BE;
+        use Layout;
+
+        let string = PascalString15::from("hello").unwrap();
+        let mut compact = Vec::new();
+        string.write_to(&mut compact, Layout::Compact).unwrap();
+        assert_eq!(&compact[..], b"\x05hello");
+        let mut record = Vec::new();
+        string.write_to(&mut record, Layout::Record).unwrap();
+        assert_eq!(record.len(), 16);
+        assert_eq!(&record[..6], b"\x05hello");
+        assert!(record[6..].iter().all(|&b| b == 0));
+
+        let mut reader = Cursor::new(b"\x02hi\x05hello".to_vec());
+        assert_eq!(PascalString15::read_from(&mut reader, Layout::Compact).unwrap(), "hi");
+        assert_eq!(PascalString15::read_from(&mut reader, Layout::Compact).unwrap(), "hello");
+        let mut reader = Cursor::new(record);
+        assert_eq!(PascalString15::read_from(&mut reader, Layout::Record).unwrap(), "hello");
+        assert_eq!(reader.position(), 16);
+
+        let wide = PascalString::<4, U16BE>::from("abc").unwrap();
+        let mut buf = Vec::new();
+        wide.write_to(&mut buf, Layout::Record).unwrap();
+        assert_eq!(&buf[..], b"\x00\x03abc\x00");
+        let read = PascalString::<4, U16BE>::read_from(&mut &buf[..], Layout::Record).unwrap();
+        assert_eq!(read, "abc");
+
+        let err = PascalString15::read_from(&mut &b"\x05hel"[..], Layout::Compact).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = PascalString15::read_from(&mut &b"\x02hi"[..], Layout::Record).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = PascalString15::read_from(&mut &b"\x10"[..], Layout::Compact).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.get_ref().unwrap().downcast_ref::<PascalStringCreateError>(),
+                   Some(&PascalStringCreateError::InputTooLong { required: 16, available: 15 }));
+        let err = PascalString15::read_from(&mut &b"\x02h\xff"[..], Layout::Compact).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Readable, Token};
+
+        let string = PascalString15::from("hello").unwrap();
+        assert_tokens(&string.clone().readable(), &[Token::Str("hello")]);
+        assert_tokens(&string.compact(), &[Token::Bytes(b"hello")]);
+        assert_de_tokens_error::<Readable<PascalString15>>(&[Token::Str("hello, world!!!!")],
+                                                 &PascalString15::from("hello, world!!!!").unwrap_err().to_string());
+        assert_de_tokens_error::<Readable<PascalString15>>(&[Token::Str("señor")],
+                                                 &PascalString15::from("señor").unwrap_err().to_string());
+
+        let mut tokens = vec![Token::Seq { len: Some(20) }];
+        tokens.extend((0..20).map(|_| Token::U8(b'a')));
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens_error::<Readable<PascalString15>>(&tokens,
+                                                 &PascalString15::from([b'a'; 20]).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_const_construction() {
+        const EMPTY: PascalString15 = PascalString::new();
+        static COMMANDS: [PascalString15; 3] = [pstr!("open"), pstr!("close"), pstr!("")];
+        assert!(EMPTY.is_empty());
+        assert_eq!(COMMANDS[0], "open");
+        assert_eq!(COMMANDS[1].len(), 5);
+        assert_eq!(COMMANDS[1].capacity(), 15);
+        assert!(COMMANDS[2].is_empty());
+
+        let string: PascalString15 = pstr!("hello");
+        assert_eq!(string, PascalString15::from("hello").unwrap());
+        assert_eq!(PascalString15::from_literal("hello"), string);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_literal_too_long() {
+        let _ = PascalString15::from_literal("hello, world!!!!");
+    }
+
+    #[test]
+    fn test_format() {
+        use core::fmt::Write;
+
+        let mut string = PascalString::<8>::new();
+        write!(string, "{}+{}", 1, 2).unwrap();
+        assert_eq!(string, "1+2");
+        assert!(write!(string, "={:>6}", 3).is_err());
+        assert_eq!(string, "1+2=    ");
+        assert!(string.write_char('ñ').is_err());
+
+        let formatted: PascalString15 = pformat!("{:?}", Some(1)).unwrap();
+        assert_eq!(formatted, "Some(1)");
+        let result: Result<PascalString<4>, _> = pformat!("{}", 12345);
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 5, available: 4 }));
+        let result: Result<PascalString<4>, _> = pformat!("{}-{}", 1234, 5678);
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 9, available: 4 }));
+        let result: Result<PascalString15, _> = pformat!("{}", "señor");
+        assert!(matches!(result, Err(PascalStringAppendError::NotValidAscii(_))));
+
+        let truncated: PascalString<4> = pformat!(truncate; "{}{}", 123, 456).unwrap();
+        assert_eq!(truncated, "1234");
+        let result: Result<PascalString<4>, _> = pformat!(truncate; "{}", "ñ");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_search() {
+        let string = PascalString15::from("Hello, world!").unwrap();
+        assert_eq!(string.find('o'), Some(4));
+        assert_eq!(string.rfind('o'), Some(8));
+        assert_eq!(string.find(AsciiChar::W), None);
+        assert_eq!(string.find(b'w'), Some(7));
+        assert_eq!(string.find('ñ'), None);
+        assert_eq!(string.find(&[b',', b'!'][..]), Some(5));
+        assert_eq!(string.rfind(b",!"), Some(12));
+        assert_eq!(string.find(&['l', 'w']), Some(2));
+        assert_eq!(string.find("world"), Some(7));
+        assert_eq!(string.find("word"), None);
+        assert_eq!(string.find(|ch: AsciiChar| ch.is_ascii_whitespace()), Some(6));
+        assert_eq!(string.find(""), Some(0));
+        assert_eq!(string.rfind(""), Some(13));
+
+        let idx = string.find("world").unwrap();
+        assert_eq!(&string[idx..idx + 5], AsciiStr::from_ascii("world").unwrap().as_slice());
+        assert_eq!(&string[idx + 5..], &[AsciiChar::Exclamation]);
+
+        assert!(string.contains("lo, "));
+        assert!(!string.contains('z'));
+        assert!(string.starts_with("Hell"));
+        assert!(string.starts_with(AsciiChar::H));
+        assert!(!string.starts_with("hell"));
+        assert!(string.ends_with("world!"));
+        assert!(string.ends_with(|ch: AsciiChar| ch.is_ascii_punctuation()));
+        assert!(string.ends_with(""));
+
+        let indices: Vec<_> = string.match_indices('l').map(|(i, m)| (i, m.as_str())).collect();
+        assert_eq!(indices, [(2, "l"), (3, "l"), (10, "l")]);
+        let needle = PascalString15::from("ll").unwrap();
+        assert_eq!(string.matches(&*needle).count(), 1);
+        let aaaa = PascalString15::from("aaaa").unwrap();
+        assert_eq!(aaaa.matches("aa").count(), 2);
+        let empty: Vec<_> = PascalString15::from("ab").unwrap().match_indices("").map(|(i, _)| i).collect();
+        assert_eq!(empty, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_search_past_255() {
+        use prefix::U16BE;
+
+        let mut string = PascalString::<300, U16BE>::new();
+        for _ in 0..299 {
+            string.push('a');
+        }
+        string.push('b');
+        assert_eq!(string.find('b'), Some(299));
+        assert_eq!(string.rfind('a'), Some(298));
+        assert_eq!(string.match_indices('b').map(|(i, m)| (i, m.as_str())).next(), Some((299, "b")));
+    }
+
+    #[test]
+    fn test_split() {
+        fn collect<'a, I: Iterator<Item = &'a PascalStr>>(iter: I) -> Vec<&'a str> {
+            iter.map(|s| s.as_str()).collect()
+        }
+
+        let string = PascalString31::from("a,b,,c,").unwrap();
+        let expected: Vec<_> = "a,b,,c,".split(',').collect();
+        assert_eq!(collect(string.split(',')), expected);
+        let expected: Vec<_> = "a,b,,c,".rsplit(',').collect();
+        assert_eq!(collect(string.rsplit(',')), expected);
+        assert_eq!(collect(string.splitn(3, ',')), ["a", "b", ",c,"]);
+        assert_eq!(string.splitn(0, ',').count(), 0);
+        assert_eq!(collect(string.split_terminator(',')), ["a", "b", "", "c"]);
+        assert_eq!(collect(string.split(",,")), ["a,b", "c,"]);
+
+        let empty: Vec<_> = "ab".split("").collect();
+        assert_eq!(collect(PascalString15::from("ab").unwrap().split("")), empty);
+        let empty: Vec<_> = "ab".rsplit("").collect();
+        assert_eq!(collect(PascalString15::from("ab").unwrap().rsplit("")), empty);
+
+        let record = PascalString31::from("  key \t value\n").unwrap();
+        assert_eq!(collect(record.split_whitespace()), ["key", "value"]);
+        let (key, value) = record.split_once(&[' ', '\t'][..]).unwrap();
+        assert_eq!((key.as_str(), value.as_str()), ("", " key \t value\n"));
+        let pair = PascalString15::from("key=value").unwrap();
+        let (key, value) = pair.split_once('=').unwrap();
+        assert_eq!((key.as_str(), value.as_str()), ("key", "value"));
+        assert!(pair.split_once(';').is_none());
+    }
+
+    #[test]
+    fn test_error_details() {
+        let error = PascalString15::from("hello, world!!!!").unwrap_err();
+        assert_eq!(error, PascalStringCreateError::InputTooLong { required: 16, available: 15 });
+        assert_eq!(error.valid_up_to(), 15);
+        assert_eq!(error.unconsumed("hello, world!!!!"), b"!");
+        let error = PascalString15::from("héllo").unwrap_err();
+        assert_eq!(error.valid_up_to(), 1);
+        assert_eq!(error.unconsumed("héllo"), "éllo".as_bytes());
+
+        let mut string = PascalString15::from("hello, world").unwrap();
+        let error = string.try_push_str("!!!!").unwrap_err();
+        assert_eq!(error, PascalStringAppendError::NoRoom { required: 4, available: 3 });
+        assert_eq!(error.unconsumed("!!!!"), b"!");
+        #[cfg(feature = "alloc")]
+        assert_eq!(error.to_string(),
+                   "there is no space left in the string to append the data (4 bytes required, 3 available)");
+        assert_eq!(string, "hello, world");
+
+        let error = PascalStr::from_prefixed_bytes(b"\x06short").unwrap_err();
+        assert_eq!(error, PrefixedBytesError::Truncated { required: 7, available: 6 });
+    }
+
+    #[test]
+    fn test_utf8_conversion() {
+        use std::convert::TryFrom;
+        use ::utf8;
+
+        let string = utf8::PascalString15::from_str("hello").unwrap();
+        let ascii = PascalString15::try_from(string.clone()).unwrap();
+        assert_eq!(ascii, "hello");
+        assert_eq!(ascii, string);
+        assert_eq!(<&PascalStr>::try_from(&*string).unwrap(), &*ascii);
+
+        let string = utf8::PascalString15::from_str("hé, señor").unwrap();
+        let (_, rest) = string.split_once(',').unwrap();
+        assert_eq!(<&PascalStr>::try_from(rest).unwrap_err().valid_up_to(), 3);
+        let error = PascalString15::try_from(string).unwrap_err();
+        assert_eq!(error.valid_up_to(), 1);
+
+        let smaller = PascalString15::from("abc").unwrap();
+        let larger = utf8::PascalString31::from_str("abd").unwrap();
+        assert!(smaller < larger);
+        assert!(larger > smaller);
+        assert!(*smaller < *larger);
+        assert!(*larger > *smaller);
+        assert!(smaller != larger);
+    }
 }
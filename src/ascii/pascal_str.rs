@@ -1,15 +1,31 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::ffi::CString;
+#[cfg(feature = "alloc")]
+use ascii_crate::AsciiString;
 use ascii_crate::{AsciiChar, AsciiStr};
+#[cfg(feature = "alloc")]
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, Iterator};
+use core::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeTo};
+use core::slice::{Iter, IterMut};
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
 use std::ascii::AsciiExt;
-use std::borrow::{Cow, ToOwned};
-use std::cmp::Ordering;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::ffi::{CStr, CString};
-use std::iter::{ExactSizeIterator, Iterator};
-use std::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeTo};
-use std::slice::{Iter, IterMut};
-use std::{fmt, isize};
-use ::ascii::PascalString;
-use ::PASCAL_STRING_BUF_SIZE;
+use ::ascii::{AsciiError, MatchIndices, Matches, Pattern};
+use ::ascii::{RSplit, Split, SplitN, SplitTerminator, SplitWhitespace};
+use ::ascii::pattern::Searcher;
+use ::ascii::{PascalString, PascalStringAppendError};
+use ::prefix::{LengthPrefix, U8};
+use ::utf8;
 
 /// A borrowed slice from a `PascalString`. Does not own its data.
 #[derive(Eq, Hash, Ord)]
@@ -19,6 +35,38 @@ pub struct PascalStr {
 }
 
 impl PascalStr {
+    /// Borrow a `PascalStr` from the start of a buffer which holds a pascal string with a single byte
+    /// length prefix.
+    ///
+    /// The string is not copied. On success, the `PascalStr` is returned along with the remainder of
+    /// `bytes` which follows the end of the string.
+    ///
+    /// Returns an `Err` if `bytes` is shorter than the length given by its first byte, or if the string
+    /// data is not valid ascii.
+    #[inline]
+    pub fn from_prefixed_bytes(bytes: &[u8]) -> Result<(&PascalStr, &[u8]), PrefixedBytesError> {
+        PascalStr::from_prefixed_bytes_with::<U8>(bytes)
+    }
+
+    /// Borrow a `PascalStr` from the start of a buffer which holds a pascal string with the length
+    /// prefix `L`.
+    ///
+    /// See `PascalStr::from_prefixed_bytes()` for details.
+    pub fn from_prefixed_bytes_with<L: LengthPrefix>(bytes: &[u8])
+                                                     -> Result<(&PascalStr, &[u8]), PrefixedBytesError> {
+        if bytes.len() < L::WIDTH {
+            return Err(PrefixedBytesError::Truncated { required: L::WIDTH, available: bytes.len() });
+        }
+        let len = L::to_usize(L::read_len(bytes));
+        if bytes.len() < L::WIDTH + len {
+            return Err(PrefixedBytesError::Truncated { required: L::WIDTH + len, available: bytes.len() });
+        }
+        let bytes = &bytes[L::WIDTH..];
+        let (string, rest) = bytes.split_at(len);
+        let ascii = AsciiStr::from_ascii(string)?;
+        Ok((From::from(ascii), rest))
+    }
+
     /// Get a pointer to the first byte of the string buffer.
     #[inline]
     pub fn as_ptr(&self) -> *const AsciiChar {
@@ -39,17 +87,16 @@ impl PascalStr {
 
     /// Get this string as a `CStr`.
     ///
-    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If this string is full,
-    /// then a new `CString` will be allocated to hold the trailing null byte.
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If the last character of
+    /// this string is not a null character, then a new `CString` will be allocated to hold the trailing null
+    /// byte.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn as_cstr(&self) -> Result<Cow<CStr>, InteriorNullError> {
-        match self.chars().position(|&c| c == AsciiChar::Null) {
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        match self.chars().position(|&ch| ch == AsciiChar::Null) {
             Some(pos) if pos != (self.len() - 1) => Err(InteriorNullError(pos)),
-            _ if self.is_full() && self.string[PASCAL_STRING_BUF_SIZE - 1] != AsciiChar::Null => {
-                let str_clone = self.to_owned();
-                Ok(Cow::Owned(CString::new(str_clone).unwrap()))
-            }
-            _ => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(self.as_ref()).unwrap()))
+            Some(_) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(self.as_ref()).unwrap())),
+            None => Ok(Cow::Owned(CString::new(self.as_str()).unwrap()))
         }
     }
 
@@ -65,74 +112,274 @@ impl PascalStr {
         self.len() == 0
     }
 
-    /// Returns true if the string has a length of 255.
-    ///
-    /// When this value is true, no more elements can be pushed onto the string.
-    #[inline]
-    pub fn is_full(&self) -> bool {
-        self.len() == PASCAL_STRING_BUF_SIZE
-    }
-
     /// Get an immutable iterator to the internal character array.
     #[inline]
-    pub fn chars(&self) -> Chars {
+    pub fn chars(&self) -> Chars<'_> {
         Chars(self.string.as_slice().iter())
     }
 
     /// Get a mutable iterator to the internal character array.
     #[inline]
-    pub fn chars_mut(&mut self) -> CharsMut {
+    pub fn chars_mut(&mut self) -> CharsMut<'_> {
         CharsMut(self.string.as_mut_slice().iter_mut())
     }
 
     /// Get an iterator over the lines of the internal character array.
+    ///
+    /// As with `str::lines`, lines are ended by either `\n` or `\r\n`, the line endings are not included in
+    /// the lines, and the final line ending is optional.
     #[inline]
-    pub fn lines(&self) -> Lines {
+    pub fn lines(&self) -> Lines<'_> {
+        self.lines_with(LineEnding::LfOrCrLf)
+    }
+
+    /// Get an iterator over the lines of the internal character array, which are ended by `ending`.
+    #[inline]
+    pub fn lines_with(&self, ending: LineEnding) -> Lines<'_> {
         Lines {
-            current_index: 0,
-            string: &self
+            string: &self.string,
+            front: 0,
+            back: self.len(),
+            ending: ending
         }
     }
 
-    /// Get a character in the string, without checking if the index is within the bounds of `len()`.
+    /// Returns the index of the first character of the first match of `pat` in this string, or `None` if
+    /// it does not match.
     ///
-    /// This method cannot cause memory unsafety because `index` is bounds checked within the maximum possible
-    /// length of the `PascalStr`, which means that it cannot read uninitialised memory. However, it can give access
-    /// to stale characters if `index` is greater than or equal to `self.len()` or `isize::MAX`, and `self.is_full()`
-    /// is `false`.
+    /// See `Pattern` for the types which can be searched for.
+    #[inline]
+    pub fn find<P: Pattern>(&self, pat: P) -> Option<usize> {
+        Searcher::new(&self.string, pat).next_match().map(|(start, _)| start)
+    }
+
+    /// Returns the index of the first character of the last match of `pat` in this string, or `None` if
+    /// it does not match.
+    #[inline]
+    pub fn rfind<P: Pattern>(&self, pat: P) -> Option<usize> {
+        Searcher::new(&self.string, pat).next_match_back().map(|(start, _)| start)
+    }
+
+    /// Returns true if `pat` matches anywhere in this string.
+    #[inline]
+    pub fn contains<P: Pattern>(&self, pat: P) -> bool {
+        Searcher::new(&self.string, pat).next_match().is_some()
+    }
+
+    /// Returns true if `pat` matches at the start of this string.
+    #[inline]
+    pub fn starts_with<P: Pattern>(&self, mut pat: P) -> bool {
+        pat.match_at(&self.string, 0).is_some()
+    }
+
+    /// Returns true if `pat` matches at the end of this string.
+    pub fn ends_with<P: Pattern>(&self, mut pat: P) -> bool {
+        let len = self.len();
+        (0..len + 1).rev().any(|i| pat.match_at(&self.string, i) == Some(len - i))
+    }
+
+    /// Returns an iterator over the non-overlapping matches of `pat` in this string, along with the index
+    /// of the first character of each match.
+    #[inline]
+    pub fn match_indices<P: Pattern>(&self, pat: P) -> MatchIndices<'_, P> {
+        MatchIndices::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the non-overlapping matches of `pat` in this string.
+    #[inline]
+    pub fn matches<P: Pattern>(&self, pat: P) -> Matches<'_, P> {
+        Matches::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the parts of this string separated by `pat`.
+    #[inline]
+    pub fn split<P: Pattern>(&self, pat: P) -> Split<'_, P> {
+        Split::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the parts of this string separated by `pat`, starting from the end.
+    #[inline]
+    pub fn rsplit<P: Pattern>(&self, pat: P) -> RSplit<'_, P> {
+        RSplit::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over at most `n` parts of this string separated by `pat`. The last part
+    /// contains the remainder of the string.
+    #[inline]
+    pub fn splitn<P: Pattern>(&self, n: usize, pat: P) -> SplitN<'_, P> {
+        SplitN::new(&self.string, pat, n)
+    }
+
+    /// Returns an iterator over the parts of this string separated by `pat`, without an empty part after
+    /// a trailing match.
+    #[inline]
+    pub fn split_terminator<P: Pattern>(&self, pat: P) -> SplitTerminator<'_, P> {
+        SplitTerminator::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the parts of this string separated by any amount of ascii whitespace.
+    #[inline]
+    pub fn split_whitespace(&self) -> SplitWhitespace<'_> {
+        SplitWhitespace::new(&self.string)
+    }
+
+    /// Splits this string around the first match of `pat`, or returns `None` if it does not match.
+    #[inline]
+    pub fn split_once<P: Pattern>(&self, pat: P) -> Option<(&PascalStr, &PascalStr)> {
+        let string: &AsciiStr = &self.string;
+        Searcher::new(string, pat).next_match().map(|(start, len)| {
+            (From::from(&string[..start]), From::from(&string[start + len..]))
+        })
+    }
+
+    /// Returns this string with leading and trailing ascii whitespace removed.
+    #[inline]
+    pub fn trim(&self) -> &PascalStr {
+        self.trim_matches(|ch: AsciiChar| ch.is_ascii_whitespace())
+    }
+
+    /// Returns this string with leading ascii whitespace removed.
+    #[inline]
+    pub fn trim_start(&self) -> &PascalStr {
+        self.trim_start_matches(|ch: AsciiChar| ch.is_ascii_whitespace())
+    }
+
+    /// Returns this string with trailing ascii whitespace removed.
+    #[inline]
+    pub fn trim_end(&self) -> &PascalStr {
+        self.trim_end_matches(|ch: AsciiChar| ch.is_ascii_whitespace())
+    }
+
+    /// Returns this string with all leading and trailing matches of `pat` removed.
+    #[inline]
+    pub fn trim_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let start = self.trim_start_index(&mut pat);
+        let end = self.trim_end_index(&mut pat, start);
+        From::from(&self.string[start..end])
+    }
+
+    /// Returns this string with all leading matches of `pat` removed.
+    #[inline]
+    pub fn trim_start_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let start = self.trim_start_index(&mut pat);
+        From::from(&self.string[start..])
+    }
+
+    /// Returns this string with all trailing matches of `pat` removed.
+    #[inline]
+    pub fn trim_end_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let end = self.trim_end_index(&mut pat, 0);
+        From::from(&self.string[..end])
+    }
+
+    /// The index of the first character which is not part of a match of `pat` at the start of the string.
+    fn trim_start_index<P: Pattern>(&self, pat: &mut P) -> usize {
+        let mut start = 0;
+        loop {
+            match pat.match_at(&self.string, start) {
+                Some(len) if len > 0 => start += len,
+                _ => return start
+            }
+        }
+    }
+
+    /// The index after the last character which is not part of a match of `pat` at the end of the string,
+    /// which is never less than `start`.
+    fn trim_end_index<P: Pattern>(&self, pat: &mut P, start: usize) -> usize {
+        let mut end = self.len();
+        while let Some(i) = (start..end).rev().find(|&i| pat.match_at(&self.string, i) == Some(end - i)) {
+            end = i;
+        }
+        end
+    }
+
+    /// Replace all matches of `from` with `to`, writing the result into a new `PascalString`. The capacity
+    /// and length prefix of the result are taken from the type it is used as.
     ///
-    /// # Panics
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the `PascalString`, or
+    /// `Err(PascalStringAppendError::NotValidAscii)` if `to` is not valid ascii.
+    #[inline]
+    pub fn replace<P, S, const N: usize, L>(&self, from: P, to: S)
+                                            -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), usize::MAX, false)
+    }
+
+    /// Replace the first `count` matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The capacity and length prefix of the result are taken from the type it is used as.
     ///
-    /// This method will panic if `index` is larger than `u8::MAX`(255).
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the `PascalString`, or
+    /// `Err(PascalStringAppendError::NotValidAscii)` if `to` is not valid ascii.
     #[inline]
-    pub fn get_unchecked(&self, index: usize) -> AsciiChar {
-        assert!(index < PASCAL_STRING_BUF_SIZE);
-        let ptr = self.as_ptr();
-        unsafe {
-            *ptr.offset(index as isize)
+    pub fn replacen<P, S, const N: usize, L>(&self, from: P, to: S, count: usize)
+                                             -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), count, false)
+    }
+
+    /// Replace all matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The result is truncated to the capacity of the `PascalString`.
+    ///
+    /// Returns `Err(PascalStringAppendError::NotValidAscii)` if `to` is not valid ascii.
+    #[inline]
+    pub fn replace_truncated<P, S, const N: usize, L>(&self, from: P, to: S)
+                                                      -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), usize::MAX, true)
+    }
+
+    /// Replace the first `count` matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The result is truncated to the capacity of the `PascalString`.
+    ///
+    /// Returns `Err(PascalStringAppendError::NotValidAscii)` if `to` is not valid ascii.
+    #[inline]
+    pub fn replacen_truncated<P, S, const N: usize, L>(&self, from: P, to: S, count: usize)
+                                                       -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), count, true)
+    }
+
+    fn replace_into<P, const N: usize, L>(&self, from: P, to: &str, count: usize, truncate: bool)
+                                          -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, L: LengthPrefix {
+        let to = AsciiStr::from_ascii(to)?;
+        let string: &AsciiStr = &self.string;
+        let mut result = PascalString::new();
+        {
+            let mut push = |s: &str| if truncate { result.push_str_truncated(s) } else { result.try_push_str(s) };
+            let mut searcher = Searcher::new(string, from);
+            let mut last = 0;
+            for _ in 0..count {
+                let (start, len) = match searcher.next_match() {
+                    Some(found) => found,
+                    None => break
+                };
+                push(string[last..start].as_str())?;
+                push(to.as_str())?;
+                last = start + len;
+            }
+            push(string[last..].as_str())?;
         }
+        Ok(result)
     }
+
 }
 
+#[cfg(feature = "std")]
+#[allow(deprecated)]
 impl AsciiExt for PascalStr {
-    type Owned = PascalString;
+    type Owned = AsciiString;
 
     fn is_ascii(&self) -> bool {
         true
     }
 
     fn to_ascii_uppercase(&self) -> Self::Owned {
-        let bytes: &[u8] = self.as_ref();
-        let mut upper = PascalString::from(bytes).unwrap();
-        upper.make_ascii_uppercase();
-        upper
+        self.string.to_ascii_uppercase()
     }
 
     fn to_ascii_lowercase(&self) -> Self::Owned {
-        let bytes: &[u8] = self.as_ref();
-        let mut lower = PascalString::from(bytes).unwrap();
-        lower.make_ascii_lowercase();
-        lower
+        self.string.to_ascii_lowercase()
     }
 
     fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
@@ -172,22 +419,69 @@ impl<S: AsRef<str> + ?Sized> PartialOrd<S> for PascalStr {
     #[inline]
     fn partial_cmp(&self, other: &S) -> Option<Ordering> {
         let other = other.as_ref();
-        self.as_str().partial_cmp(&other)
+        self.as_str().partial_cmp(other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ToOwned for PascalStr {
-    type Owned = PascalString;
+    /// A `PascalStr` may be longer than the default `PascalString` can hold, so it is copied into an
+    /// `AsciiString`.
+    type Owned = AsciiString;
     #[inline]
     fn to_owned(&self) -> Self::Owned {
-        PascalString::from(&self.string).unwrap()
+        self.string.to_ascii_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow<PascalStr> for AsciiString {
+    #[inline]
+    fn borrow(&self) -> &PascalStr {
+        From::from(&**self)
+    }
+}
+
+impl Default for &PascalStr {
+    #[inline]
+    fn default() -> Self {
+        From::from(<&AsciiStr>::default())
+    }
+}
+
+impl<'a> From<&'a AsciiStr> for &'a PascalStr {
+    #[inline]
+    fn from(string: &'a AsciiStr) -> Self {
+        unsafe {
+            mem::transmute(string)
+        }
+    }
+}
+
+impl<'a> From<&'a mut AsciiStr> for &'a mut PascalStr {
+    #[inline]
+    fn from(string: &'a mut AsciiStr) -> Self {
+        unsafe {
+            mem::transmute(string)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a utf8::PascalStr> for &'a PascalStr {
+    type Error = AsciiError;
+
+    /// Borrows a `utf8::PascalStr` as an ascii `PascalStr`, failing if it contains any non-ascii characters.
+    #[inline]
+    fn try_from(string: &'a utf8::PascalStr) -> Result<Self, AsciiError> {
+        let ascii = AsciiStr::from_ascii(string.as_str())?;
+        Ok(From::from(ascii))
     }
 }
 
 impl AsRef<PascalStr> for PascalStr {
     #[inline]
     fn as_ref(&self) -> &Self {
-        &self
+        self
     }
 }
 
@@ -292,7 +586,7 @@ impl Index<RangeFull> for PascalStr {
     #[inline]
     fn index(&self, _: RangeFull) -> &Self::Output {
         let char_array: &[AsciiChar] = self.string.as_ref();
-        &char_array[..]
+        char_array
     }
 }
 
@@ -308,7 +602,7 @@ impl Index<Range<u8>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: Range<u8>) -> &Self::Output {
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[range.start as usize..range.end as usize]
     }
@@ -317,7 +611,7 @@ impl Index<Range<u8>> for PascalStr {
 impl IndexMut<Range<u8>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: Range<u8>) -> &mut Self::Output {
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[range.start as usize..range.end as usize]
     }
@@ -327,7 +621,7 @@ impl Index<Range<usize>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: Range<usize>) -> &Self::Output {
-        assert!(range.end < self.len());
+        assert!(range.end <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[range.start..range.end]
     }
@@ -336,7 +630,7 @@ impl Index<Range<usize>> for PascalStr {
 impl IndexMut<Range<usize>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: Range<usize>) -> &mut Self::Output {
-        assert!(range.end < self.len());
+        assert!(range.end <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[range.start..range.end]
     }
@@ -347,11 +641,11 @@ impl Index<Range<i32>> for PascalStr {
     #[inline]
     fn index(&self, range: Range<i32>) -> &Self::Output {
         assert!(range.start >= 0);
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         assert!(range.end >= 0);
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
-        &char_array[range.start as usize..]
+        &char_array[range.start as usize..range.end as usize]
     }
 }
 
@@ -359,11 +653,11 @@ impl IndexMut<Range<i32>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: Range<i32>) -> &mut Self::Output {
         assert!(range.start >= 0);
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         assert!(range.end >= 0);
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
-        &mut char_array[range.start as usize..]
+        &mut char_array[range.start as usize..range.end as usize]
     }
 }
 
@@ -371,7 +665,7 @@ impl Index<RangeFrom<u8>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: RangeFrom<u8>) -> &Self::Output {
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[range.start as usize..]
     }
@@ -380,7 +674,7 @@ impl Index<RangeFrom<u8>> for PascalStr {
 impl IndexMut<RangeFrom<u8>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeFrom<u8>) -> &mut Self::Output {
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[range.start as usize..]
     }
@@ -390,7 +684,7 @@ impl Index<RangeFrom<usize>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: RangeFrom<usize>) -> &Self::Output {
-        assert!(range.start < self.len());
+        assert!(range.start <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[range.start..]
     }
@@ -399,7 +693,7 @@ impl Index<RangeFrom<usize>> for PascalStr {
 impl IndexMut<RangeFrom<usize>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeFrom<usize>) -> &mut Self::Output {
-        assert!(range.start < self.len());
+        assert!(range.start <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[range.start..]
     }
@@ -410,7 +704,7 @@ impl Index<RangeFrom<i32>> for PascalStr {
     #[inline]
     fn index(&self, range: RangeFrom<i32>) -> &Self::Output {
         assert!(range.start >= 0);
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[range.start as usize..]
     }
@@ -420,7 +714,7 @@ impl IndexMut<RangeFrom<i32>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeFrom<i32>) -> &mut Self::Output {
         assert!(range.start >= 0);
-        assert!((range.start as usize) < self.len());
+        assert!((range.start as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[range.start as usize..]
     }
@@ -430,7 +724,7 @@ impl Index<RangeTo<u8>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: RangeTo<u8>) -> &Self::Output {
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[..range.end as usize]
     }
@@ -439,7 +733,7 @@ impl Index<RangeTo<u8>> for PascalStr {
 impl IndexMut<RangeTo<u8>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeTo<u8>) -> &mut Self::Output {
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[..range.end as usize]
     }
@@ -449,7 +743,7 @@ impl Index<RangeTo<usize>> for PascalStr {
     type Output = [AsciiChar];
     #[inline]
     fn index(&self, range: RangeTo<usize>) -> &Self::Output {
-        assert!(range.end < self.len());
+        assert!(range.end <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[..range.end]
     }
@@ -458,7 +752,7 @@ impl Index<RangeTo<usize>> for PascalStr {
 impl IndexMut<RangeTo<usize>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeTo<usize>) -> &mut Self::Output {
-        assert!(range.end < self.len());
+        assert!(range.end <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[..range.end]
     }
@@ -469,7 +763,7 @@ impl Index<RangeTo<i32>> for PascalStr {
     #[inline]
     fn index(&self, range: RangeTo<i32>) -> &Self::Output {
         assert!(range.end >= 0);
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &[AsciiChar] = self.string.as_ref();
         &char_array[..range.end as usize]
     }
@@ -479,7 +773,7 @@ impl IndexMut<RangeTo<i32>> for PascalStr {
     #[inline]
     fn index_mut(&mut self, range: RangeTo<i32>) -> &mut Self::Output {
         assert!(range.end >= 0);
-        assert!((range.end as usize) < self.len());
+        assert!((range.end as usize) <= self.len());
         let char_array: &mut [AsciiChar] = self.string.as_mut();
         &mut char_array[..range.end as usize]
     }
@@ -543,37 +837,105 @@ impl<'a> ExactSizeIterator for CharsMut<'a> {
     }
 }
 
+/// The line endings which `PascalStr::lines_with` splits lines on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Lines are ended by `\n`.
+    Lf,
+    /// Lines are ended by `\r\n`.
+    CrLf,
+    /// Lines are ended by `\r`, as on classic Mac OS.
+    Cr,
+    /// Lines are ended by either `\n` or `\r\n`. This is what `PascalStr::lines` and `str::lines` use.
+    LfOrCrLf,
+    /// Lines are ended by any of `\n`, `\r\n` or `\r`.
+    Any
+}
+
+impl LineEnding {
+    /// The byte sequences which end a line, longest first.
+    #[inline]
+    fn terminators(self) -> &'static [&'static [u8]] {
+        match self {
+            LineEnding::Lf => &[b"\n"],
+            LineEnding::CrLf => &[b"\r\n"],
+            LineEnding::Cr => &[b"\r"],
+            LineEnding::LfOrCrLf => &[b"\r\n", b"\n"],
+            LineEnding::Any => &[b"\r\n", b"\n", b"\r"]
+        }
+    }
+
+    /// Returns the length of the line ending at the start of `bytes`, if there is one.
+    #[inline]
+    fn match_start(self, bytes: &[u8]) -> Option<usize> {
+        self.terminators().iter().find(|t| bytes.starts_with(t)).map(|t| t.len())
+    }
+
+    /// Returns the length of the line ending at the end of `bytes`, if there is one.
+    #[inline]
+    fn match_end(self, bytes: &[u8]) -> Option<usize> {
+        self.terminators().iter().find(|t| bytes.ends_with(t)).map(|t| t.len())
+    }
+}
+
 /// An iterator over the lines of the internal character array.
-#[derive(Debug)]
+///
+/// This is created by `PascalStr::lines()` and `PascalStr::lines_with()`.
+#[derive(Clone, Debug)]
 pub struct Lines<'a> {
-    current_index: usize,
-    string: &'a PascalStr
+    string: &'a AsciiStr,
+    /// The start of the part of the string which has not been yielded yet.
+    front: usize,
+    /// The end of the part of the string which has not been yielded yet.
+    back: usize,
+    ending: LineEnding
 }
 
 impl<'a> Iterator for Lines<'a> {
     type Item = &'a AsciiStr;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let curr_idx = self.current_index;
-        let len = self.string.len();
-        if curr_idx >= len {
+        if self.front >= self.back {
             return None;
         }
 
-        let mut next_idx = None;
-        for i in curr_idx..len {
-            if self.string[i] == AsciiChar::LineFeed {
-                next_idx = Some(i);
-                break;
+        let bytes = &self.string.as_bytes()[self.front..self.back];
+        let start = self.front;
+        for i in 0..bytes.len() {
+            if let Some(len) = self.ending.match_start(&bytes[i..]) {
+                self.front = start + i + len; // skip the line ending
+                return Some(&self.string[start..start + i]);
             }
         }
-        let next_idx = match next_idx {
-            Some(i) => i,
-            None => return None
-        };
-        let line: &AsciiStr = From::from(&self.string[curr_idx..next_idx]);
+        self.front = self.back;
+        Some(&self.string[start..self.back])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Lines<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
 
-        self.current_index = next_idx + 1; // skip the linefeed
+        let bytes = &self.string.as_bytes()[self.front..self.back];
+        // The line ending of the last line is optional.
+        let end = bytes.len() - self.ending.match_end(bytes).unwrap_or(0);
+        for i in (0..end + 1).rev() {
+            if self.ending.match_end(&bytes[..i]).is_some() {
+                let line = &self.string[self.front + i..self.front + end];
+                self.back = self.front + i;
+                return Some(line);
+            }
+        }
+        let line = &self.string[self.front..self.front + end];
+        self.back = self.front;
         Some(line)
     }
 }
@@ -581,14 +943,22 @@ impl<'a> Iterator for Lines<'a> {
 impl<'a> ExactSizeIterator for Lines<'a> {
     #[inline]
     fn len(&self) -> usize {
-        self.string.chars().skip(self.current_index).filter(|&&c| c == AsciiChar::LineFeed).count()
+        self.clone().count()
     }
 }
 
-#[derive(Clone, Debug, Hash)]
+/// An error returned from `PascalStr::as_cstr` if the string contains an interior null character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct InteriorNullError(usize);
 
 impl InteriorNullError {
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn new(index: usize) -> Self {
+        InteriorNullError(index)
+    }
+
+    /// Returns the byte index of the interior null character.
     #[inline]
     pub fn interior_null_index(&self) -> usize {
         self.0
@@ -601,8 +971,65 @@ impl fmt::Display for InteriorNullError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InteriorNullError {
     fn description(&self) -> &str {
         "an interior null was found when creating a CStr from a pascal string"
     }
 }
+
+/// Indicates the range of errors which can occur when borrowing a `PascalStr` from a length prefixed buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixedBytesError {
+    /// The buffer is too short to hold the length prefix, or the string data which follows it.
+    Truncated {
+        /// The number of bytes needed to hold the length prefix, or the length prefix and the string data.
+        required: usize,
+        /// The length of the buffer.
+        available: usize
+    },
+    /// The string data was not correctly encoded as ascii.
+    NotValidAscii(AsciiError)
+}
+
+impl PrefixedBytesError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PrefixedBytesError::Truncated { .. } => "the buffer is shorter than the length prefix of the string",
+            PrefixedBytesError::NotValidAscii(_) => "could not convert input data to ascii"
+        }
+    }
+}
+
+impl fmt::Display for PrefixedBytesError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PrefixedBytesError::Truncated { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            PrefixedBytesError::NotValidAscii(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PrefixedBytesError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PrefixedBytesError::NotValidAscii(ref e) = *self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+}
+
+impl<E: Into<AsciiError>> From<E> for PrefixedBytesError {
+    #[inline]
+    fn from(e: E) -> Self {
+        PrefixedBytesError::NotValidAscii(e.into())
+    }
+}
@@ -1,52 +1,101 @@
-use ascii_crate::{AsAsciiStrError, AsciiChar, AsciiStr, AsciiString, ToAsciiChar, ToAsciiCharError};
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use ascii_crate::{AsAsciiStrError, AsciiChar, AsciiStr, ToAsciiChar, ToAsciiCharError};
+#[cfg(feature = "alloc")]
+use ascii_crate::AsciiString;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::{self, Ordering};
+use core::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, FromIterator, IntoIterator};
+use core::ops::{Deref, DerefMut, Range, RangeBounds};
+use core::str::{self, FromStr};
+use core::{fmt, mem, ptr, slice};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
 use std::ascii::AsciiExt;
-use std::borrow::{Borrow, BorrowMut};
-use std::cmp::Ordering;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::hash::{Hash, Hasher};
-use std::iter::{ExactSizeIterator, FromIterator, IntoIterator};
-use std::ops::{Deref, DerefMut};
-use std::str::{self, FromStr};
-use std::{fmt, mem, ptr, slice};
-use ::ascii::PascalStr;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "alloc")]
+use ::ascii::InteriorNullError;
+use ::ascii::{PascalStr, Pattern};
+use ::prefix::{LengthPrefix, U8};
+use ::utf8;
+use ::ConversionLoss;
+#[cfg(feature = "std")]
+use ::Layout;
 use ::PASCAL_STRING_BUF_SIZE;
 
-/// An owned `PascalString`. This string type stores its data the stack. It is always 256 bytes long, with
-/// the first byte storing the length.
+/// An owned `PascalString`. This string type stores its data the stack. With the default length prefix, it is
+/// always `N + 1` bytes long, with the first byte storing the length.
+///
+/// The capacity `N` defaults to 255, which gives the traditional 256 byte pascal string. Smaller capacities
+/// can be used to save space when the stored strings are known to be short, but `N` may not be larger than
+/// 255, as the length must fit into a single byte.
+///
+/// Strings longer than 255 characters can be stored by choosing a wider length prefix `L` from the `prefix`
+/// module, in which case `N` may be as large as `L::MAX_LEN`.
 ///
 /// This string type uses Ascii encoding.
-pub struct PascalString {
+pub struct PascalString<const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
     /// The length of this string.
-    len: u8,
+    len: L::Len,
     /// The characters of this string, encoded as an ascii array.
-    chars: [AsciiChar; PASCAL_STRING_BUF_SIZE]
+    chars: [AsciiChar; N]
 }
 
-impl PascalString {
+/// A `PascalString` which can hold up to 15 characters.
+pub type PascalString15 = PascalString<15>;
+
+/// A `PascalString` which can hold up to 31 characters.
+pub type PascalString31 = PascalString<31>;
+
+/// A `PascalString` which can hold up to 63 characters.
+pub type PascalString63 = PascalString<63>;
+
+/// A `PascalString` which can hold up to 255 characters.
+pub type PascalString255 = PascalString<255>;
+
+impl<const N: usize, L: LengthPrefix> PascalString<N, L> {
     /// Creates a new, empty `PascalString`.
     #[inline]
-    pub fn new() -> Self {
-        Default::default()
+    pub const fn new() -> Self {
+        let () = ::CapacityCheck::<L, N>::VALID;
+        PascalString {
+            len: L::EMPTY,
+            chars: [AsciiChar::Null; N]
+        }
     }
 
     /// Create a new `PascalString` from its constituent parts: `string_len` and `char_array`.
     ///
-    /// Returns an `Err` if `char_array` is not valid Ascii.
+    /// Returns an `Err` if `string_len` is larger than the capacity of the string, or if `char_array` is not
+    /// valid Ascii.
     #[inline]
-    pub fn from_fixed_ascii_array<C>(string_len: u8, char_array: [C; PASCAL_STRING_BUF_SIZE])
+    pub fn from_fixed_ascii_array<C>(string_len: usize, char_array: [C; N])
                                      -> Result<Self, PascalStringCreateError>
         where C: ToAsciiChar + Clone {
+        if string_len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: string_len, available: N });
+        }
         let mut pstring = PascalString::new();
-        pstring.len = string_len;
-        for i in 0..(pstring.len as usize) {
-            pstring[i] = try!(AsciiChar::from(char_array[i].clone()));
+        pstring.set_len(string_len);
+        for i in 0..string_len {
+            pstring[i] = AsciiChar::from_ascii(char_array[i].clone())?;
         }
         Ok(pstring)
     }
 
     /// Create a new `PascalString` using the contents of `bytes`.
     ///
-    /// Returns an `Err` if `bytes` is longer than 255 characters, or it does not contain
+    /// Returns an `Err` if `bytes` is longer than the capacity of the string, or it does not contain
     /// Ascii encoded characters.
     #[inline]
     pub fn from<B: AsRef<[u8]>>(bytes: B) -> Result<Self, PascalStringCreateError> {
@@ -55,20 +104,114 @@ impl PascalString {
 
     fn _from(bytes: &[u8]) -> Result<Self, PascalStringCreateError>  {
         let len = bytes.len();
-        if len > PASCAL_STRING_BUF_SIZE {
-            return Err(PascalStringCreateError::InputTooLong);
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
         }
         // Perform ascii check
-        let ascii = try!(AsciiStr::from_ascii(bytes));
+        let ascii = AsciiStr::from_ascii(bytes)?;
 
         let mut string = PascalString::new();
-        string.len = len as u8;
+        string.set_len(len);
         for i in 0..len {
             string[i] = ascii[i];
         }
         Ok(string)
     }
 
+    /// Create a new `PascalString` using as much of `bytes` as will fit into it, along with a
+    /// `ConversionLoss` which reports how many bytes were cut off.
+    ///
+    /// Returns an `Err` if the part of `bytes` which fits into the string is not valid ascii.
+    #[inline]
+    pub fn from_truncated<B: AsRef<[u8]>>(bytes: B) -> Result<(Self, ConversionLoss), PascalStringCreateError> {
+        let bytes = bytes.as_ref();
+        let len = cmp::min(bytes.len(), N);
+        let string = PascalString::_from(&bytes[..len])?;
+        Ok((string, ConversionLoss { truncated: bytes.len() - len, replaced: 0 }))
+    }
+
+    /// Create a new `PascalString` from `bytes`, replacing any bytes which are not ascii with `replacement`
+    /// (typically `AsciiChar::Question`), and cutting off any bytes which do not fit into the string.
+    ///
+    /// The returned `ConversionLoss` reports how many bytes were replaced and cut off.
+    pub fn from_lossy<B: AsRef<[u8]>>(bytes: B, replacement: AsciiChar) -> (Self, ConversionLoss) {
+        let bytes = bytes.as_ref();
+        let len = cmp::min(bytes.len(), N);
+        let mut string = PascalString::new();
+        let mut replaced = 0;
+        for (i, &byte) in bytes[..len].iter().enumerate() {
+            string.chars[i] = match AsciiChar::from_ascii(byte) {
+                Ok(ch) => ch,
+                Err(_) => {
+                    replaced += 1;
+                    replacement
+                }
+            };
+        }
+        string.set_len(len);
+        string.set_trailing_byte_to_null();
+        (string, ConversionLoss { truncated: bytes.len() - len, replaced: replaced })
+    }
+
+    /// Create a new `PascalString` from `s`, approximating accented Latin-1 letters with their ascii base
+    /// letters (so `"Café Müller"` becomes `"Cafe Muller"`), and replacing any other characters which are
+    /// not ascii with `replacement`. Characters which do not fit into the string are cut off.
+    ///
+    /// The returned `ConversionLoss` reports how many characters were replaced, and how many bytes of `s` were
+    /// cut off. Approximated letters do not count as replaced.
+    pub fn from_transliterated<S: AsRef<str>>(s: S, replacement: AsciiChar) -> (Self, ConversionLoss) {
+        let s = s.as_ref();
+        let mut string = PascalString::new();
+        let mut loss = ConversionLoss::default();
+        for (i, ch) in s.char_indices() {
+            let pushed = match (AsciiChar::from_ascii(ch), transliterate(ch)) {
+                (Ok(ascii), _) => string._try_push(ascii),
+                (Err(_), Some(approximation)) => string._try_push_str(approximation),
+                (Err(_), None) => string._try_push(replacement).map(|()| loss.replaced += 1)
+            };
+            if pushed.is_err() {
+                loss.truncated = s.len() - i;
+                break;
+            }
+        }
+        (string, loss)
+    }
+
+    /// Create a new `PascalString` from formatted text. This is usually called through the `pformat!`
+    /// macro.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the formatted text is longer than the capacity of the
+    /// string, or `Err(PascalStringAppendError::NotValidAscii)` if it is not valid ascii.
+    #[inline]
+    pub fn format(args: fmt::Arguments) -> Result<Self, PascalStringAppendError> {
+        PascalString::_format(args, false)
+    }
+
+    /// Create a new `PascalString` from formatted text, which is truncated to the capacity of the string.
+    /// This is usually called through the `pformat!` macro.
+    ///
+    /// Returns `Err(PascalStringAppendError::NotValidAscii)` if the formatted text is not valid ascii.
+    #[inline]
+    pub fn format_truncated(args: fmt::Arguments) -> Result<Self, PascalStringAppendError> {
+        PascalString::_format(args, true)
+    }
+
+    fn _format(args: fmt::Arguments, truncate: bool) -> Result<Self, PascalStringAppendError> {
+        let mut writer = FormatWriter {
+            string: PascalString::new(),
+            truncate: truncate,
+            required: 0,
+            error: None
+        };
+        match fmt::write(&mut writer, args) {
+            Ok(()) if writer.required > N && !truncate => {
+                Err(PascalStringAppendError::NoRoom { required: writer.required, available: N })
+            }
+            Ok(()) => Ok(writer.string),
+            Err(_) => Err(writer.error.expect("a formatting trait implementation returned an error"))
+        }
+    }
+
     /// Push an ascii convertible character onto this string.
     ///
     /// # Panics
@@ -85,15 +228,15 @@ impl PascalString {
     /// `character` is not a valid ascii character.
     #[inline]
     pub fn try_push<C: ToAsciiChar>(&mut self, character: C) -> Result<(), PascalStringAppendError> {
-        self._try_push(try!(AsciiChar::from(character)))
+        self._try_push(AsciiChar::from_ascii(character)?)
     }
 
     fn _try_push(&mut self, ch: AsciiChar) -> Result<(), PascalStringAppendError> {
         if self.is_full() {
-            return Err(PascalStringAppendError::NoRoom)
+            return Err(PascalStringAppendError::NoRoom { required: 1, available: 0 })
         }
-        self.len += 1;
-        let idx = self.len - 1;
+        let idx = self.len();
+        self.set_len(idx + 1);
         self[idx] = ch;
         self.set_trailing_byte_to_null();
         Ok(())
@@ -118,16 +261,26 @@ impl PascalString {
     }
 
     fn _try_push_str(&mut self, s: &str) -> Result<(), PascalStringAppendError> {
-        let ascii: &[AsciiChar] = try!(AsciiStr::from_ascii(s)).as_ref();
+        let ascii: &[AsciiChar] = AsciiStr::from_ascii(s)?.as_ref();
         let slen = self.len();
         let alen = ascii.len();
-        if slen + alen > PASCAL_STRING_BUF_SIZE {
-            return Err(PascalStringAppendError::NoRoom);
+        if slen + alen > N {
+            return Err(PascalStringAppendError::NoRoom { required: alen, available: N - slen });
         }
-        for i in 0..alen {
-            self.chars[(i + slen)] = ascii[i];
-        }
-        self.len += alen as u8;
+        self.chars[slen..slen + alen].copy_from_slice(ascii);
+        self.set_len(slen + alen);
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Append as much of `s` as will fit onto the end of this string.
+    pub(crate) fn push_str_truncated(&mut self, s: &str) -> Result<(), PascalStringAppendError> {
+        let ascii: &[AsciiChar] = AsciiStr::from_ascii(s)?.as_ref();
+        let slen = self.len();
+        let alen = ::core::cmp::min(ascii.len(), N - slen);
+        self.chars[slen..slen + alen].copy_from_slice(&ascii[..alen]);
+        self.set_len(slen + alen);
+        self.set_trailing_byte_to_null();
         Ok(())
     }
 
@@ -138,10 +291,11 @@ impl PascalString {
         if self.is_empty() {
             return None;
         }
-        let c = self.chars[self.len as usize];
-        self.len -= 1;
+        let len = self.len() - 1;
+        let ch = self.chars[len];
+        self.set_len(len);
         self.set_trailing_byte_to_null();
-        Some(c)
+        Some(ch)
     }
 
     /// Remove a character from the `AsciiString` at `index`.
@@ -149,21 +303,20 @@ impl PascalString {
     /// # Panics
     ///
     /// Panics if `index` is larger than `self.len()`, or if `self.is_empty()` is `true`.
-    pub fn remove(&mut self, index: u8) -> AsciiChar {
-        assert!(self.len > index);
+    pub fn remove(&mut self, index: usize) -> AsciiChar {
+        let len = self.len();
+        assert!(len > index);
         assert!(!self.is_empty());
-        let len = self.len as usize;
-        let index = index as usize;
-        let c = self[index];
+        let ch = self[index];
         // Shift everything to the right of the removed character to the left to cover up the hole
         // left.
         unsafe {
-            let ptr = self.as_mut_ptr().offset(index as isize);
+            let ptr = self.as_mut_ptr().add(index);
             ptr::copy(ptr.offset(1), ptr, len - index - 1);
         }
-        self.len -= 1;
+        self.set_len(len - 1);
         self.set_trailing_byte_to_null();
-        c
+        ch
     }
 
     /// Insert a character into the `AsciiString` at `index`.
@@ -172,23 +325,22 @@ impl PascalString {
     ///
     /// Panics if `index` is larger than `self.len()`, or if the `PascalString` is full.
     #[inline]
-    pub fn insert<C: ToAsciiChar>(&mut self, ch: C, index: u8) {
-        self._insert(AsciiChar::from(ch).unwrap(), index)
+    pub fn insert<C: ToAsciiChar>(&mut self, ch: C, index: usize) {
+        self._insert(AsciiChar::from_ascii(ch).unwrap(), index)
     }
 
-    fn _insert(&mut self, ch: AsciiChar, index: u8) {
-        assert!(self.len > index);
+    fn _insert(&mut self, ch: AsciiChar, index: usize) {
+        let len = self.len();
+        assert!(len >= index);
         assert!(!self.is_full());
-        let len = self.len as usize;
-        let index = index as isize;
         // Shift everything to the right of `index` 1 place to the right to make room for the
         // new character.
         unsafe {
-            let ptr = self.as_mut_ptr().offset(index);
-            ptr::copy(ptr, ptr.offset(1), len - index.abs() as usize - 1);
+            let ptr = self.chars.as_mut_ptr().add(index);
+            ptr::copy(ptr, ptr.offset(1), len - index);
         }
-        self[len] = ch;
-        self.len += 1;
+        self.chars[index] = ch;
+        self.set_len(len + 1);
         self.set_trailing_byte_to_null();
     }
 
@@ -197,18 +349,299 @@ impl PascalString {
     /// Does not zero the values of the string.
     #[inline]
     pub fn clear(&mut self) {
-        self.len = 0;
+        self.set_len(0);
+        self.set_trailing_byte_to_null();
+    }
+
+    /// Shortens this string to `new_len` characters.
+    ///
+    /// Has no effect if `new_len` is greater than or equal to the length of the string.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            self.set_len(new_len);
+            self.set_trailing_byte_to_null();
+        }
+    }
+
+    /// Splits this string in two at `at`. This string is left holding the characters before `at`, and the
+    /// characters from `at` onwards are returned in a new `PascalString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than `self.len()`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(at <= len);
+        let mut other = PascalString::new();
+        other.chars[..len - at].copy_from_slice(&self.chars[at..len]);
+        other.set_len(len - at);
+        self.truncate(at);
+        other
+    }
+
+    /// Removes the characters in `range` from this string, and returns them as an iterator.
+    ///
+    /// The characters are removed when the iterator is dropped, even if it has not been fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or if it ends after the end of the string.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, N, L> {
+        let range = ::resolve_range(range, self.len());
+        Drain {
+            string: self,
+            start: range.start,
+            end: range.end,
+            front: range.start,
+            back: range.end
+        }
+    }
+
+    /// Retains only the characters for which `f` returns `true`, removing the rest.
+    pub fn retain<F: FnMut(AsciiChar) -> bool>(&mut self, mut f: F) {
+        let len = self.len();
+        let mut kept = 0;
+        for i in 0..len {
+            let ch = self.chars[i];
+            if f(ch) {
+                self.chars[kept] = ch;
+                kept += 1;
+            }
+        }
+        self.set_len(kept);
+        self.set_trailing_byte_to_null();
+    }
+
+    /// Insert the string slice `s` into this string at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`, if there is no room to store `s`, or if `s` is not
+    /// valid ascii.
+    #[inline]
+    pub fn insert_str<S: AsRef<str>>(&mut self, s: S, index: usize) {
+        self.try_insert_str(s, index).unwrap();
+    }
+
+    /// Attempt to insert the string slice `s` into this string at `index`.
+    ///
+    /// Returns `Err(_)` if there is no room to store `s`, or if `s` is not valid ascii.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`.
+    #[inline]
+    pub fn try_insert_str<S: AsRef<str>>(&mut self, s: S, index: usize) -> Result<(), PascalStringAppendError> {
+        self._try_replace_range(index..index, s.as_ref())
+    }
+
+    /// Replace the characters in `range` with the string slice `s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, if there is no room to store `s`, or if `s` is not valid ascii.
+    #[inline]
+    pub fn replace_range<R: RangeBounds<usize>, S: AsRef<str>>(&mut self, range: R, s: S) {
+        self.try_replace_range(range, s).unwrap();
+    }
+
+    /// Attempt to replace the characters in `range` with the string slice `s`.
+    ///
+    /// Returns `Err(_)` if there is no room to store `s`, or if `s` is not valid ascii. The string is left
+    /// unchanged if an error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or if it ends after the end of the string.
+    #[inline]
+    pub fn try_replace_range<R: RangeBounds<usize>, S: AsRef<str>>(&mut self, range: R, s: S)
+                                                                   -> Result<(), PascalStringAppendError> {
+        let range = ::resolve_range(range, self.len());
+        self._try_replace_range(range, s.as_ref())
+    }
+
+    /// Replace all matches of `from` with `to` in this string.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the string, or
+    /// `Err(PascalStringAppendError::NotValidAscii)` if `to` is not valid ascii. The string is left unchanged if
+    /// an error is returned.
+    #[inline]
+    pub fn replace_in_place<P: Pattern, S: AsRef<str>>(&mut self, from: P, to: S)
+                                                       -> Result<(), PascalStringAppendError> {
+        let replaced: PascalString<N, L> = self.replace(from, to)?;
+        *self = replaced;
+        Ok(())
+    }
+
+    /// Pad the start of this string with `fill` until it is `width` characters long, right-justifying its
+    /// contents.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if `width` is larger than the capacity of the string.
+    /// Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn pad_left(&mut self, width: usize, fill: AsciiChar) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.len());
+        self.pad(width, padding, 0, fill)
+    }
+
+    /// Pad the end of this string with `fill` until it is `width` characters long, left-justifying its
+    /// contents.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if `width` is larger than the capacity of the string.
+    /// Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn pad_right(&mut self, width: usize, fill: AsciiChar) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.len());
+        self.pad(width, 0, padding, fill)
+    }
+
+    /// Pad both ends of this string with `fill` until it is `width` characters long, centering its contents.
+    /// If the padding cannot be split evenly, the extra character goes at the end.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if `width` is larger than the capacity of the string.
+    /// Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn center(&mut self, width: usize, fill: AsciiChar) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.len());
+        self.pad(width, padding / 2, padding - padding / 2, fill)
+    }
+
+    /// Add `left` copies of `fill` to the start of the string, and `right` copies to the end, checking that
+    /// the padded string is no longer than `width`.
+    fn pad(&mut self, width: usize, left: usize, right: usize, fill: AsciiChar)
+           -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        if width > N {
+            return Err(PascalStringAppendError::NoRoom { required: left + right, available: N - len });
+        }
+        self.chars.copy_within(0..len, left);
+        for ch in &mut self.chars[..left] {
+            *ch = fill;
+        }
+        for ch in &mut self.chars[left + len..left + len + right] {
+            *ch = fill;
+        }
+        self.set_len(left + len + right);
         self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    fn _try_replace_range(&mut self, range: Range<usize>, s: &str) -> Result<(), PascalStringAppendError> {
+        let ascii: &[AsciiChar] = AsciiStr::from_ascii(s)?.as_ref();
+        let len = self.len();
+        assert!(range.start <= range.end && range.end <= len);
+        let kept = len - (range.end - range.start);
+        if kept + ascii.len() > N {
+            return Err(PascalStringAppendError::NoRoom { required: ascii.len(), available: N - kept });
+        }
+        self.chars.copy_within(range.end..len, range.start + ascii.len());
+        self.chars[range.start..range.start + ascii.len()].copy_from_slice(ascii);
+        self.set_len(kept + ascii.len());
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Read a `PascalString` from `reader`, which holds a length prefix followed by the string data, laid
+    /// out as described by `layout`.
+    ///
+    /// If the length prefix is larger than the capacity of the string, or the string data is not valid
+    /// ascii, then an error of kind `io::ErrorKind::InvalidData` is returned, which wraps a
+    /// `PascalStringCreateError`.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R, layout: Layout) -> io::Result<Self> {
+        let len = ::read_prefix::<L, R>(reader)?;
+        if len > N {
+            let error = PascalStringCreateError::InputTooLong { required: len, available: N };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        let mut bytes = [0u8; N];
+        reader.read_exact(&mut bytes[..len])?;
+        if layout == Layout::Record {
+            ::skip_bytes(reader, N - len)?;
+        }
+        PascalString::_from(&bytes[..len]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
 
-    /// Consumes this `PascalString`, and returns its inner state as a `[u8; 256]`, where the first byte
-    /// is the length.
+    /// Write this `PascalString` to `writer`, as a length prefix followed by the string data, laid out
+    /// as described by `layout`.
+    ///
+    /// Unlike `to_array()`, stale bytes past the end of the string are never written.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_to<W: Write>(&self, writer: &mut W, layout: Layout) -> io::Result<()> {
+        ::write_prefixed::<L, W>(writer, self.as_ref(), N, layout)
+    }
+
+    /// Returns the maximum number of characters which this `PascalString` can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the string has a length equal to its capacity.
+    ///
+    /// When this value is true, no more elements can be pushed onto the string.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Get a character in the string, without checking if the index is within the bounds of `len()`.
+    ///
+    /// This method cannot cause memory unsafety because `index` is bounds checked within the capacity of
+    /// the `PascalString`, which means that it cannot read uninitialised memory. However, it can give access
+    /// to stale characters if `index` is greater than or equal to `self.len()`, and `self.is_full()` is `false`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `index` is larger than or equal to the capacity of the string.
+    #[inline]
+    pub fn get_unchecked(&self, index: usize) -> AsciiChar {
+        self.chars[index]
+    }
+
+    /// Get this string as a `CStr`.
+    ///
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If this string is not
+    /// full, then the trailing null byte which is kept past the end of the string is used, so no allocation
+    /// is made. If the string is full, then a new `CString` will be allocated to hold the trailing null byte.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        if self.is_full() {
+            return self.deref().as_cstr();
+        }
+        let len = self.len();
+        let bytes = unsafe {
+            slice::from_raw_parts(self.chars.as_ptr() as *const u8, len + 1)
+        };
+        match bytes.iter().position(|&b| b == 0) {
+            Some(pos) if pos < len && pos != len - 1 => Err(InteriorNullError::new(pos)),
+            Some(pos) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(&bytes[..pos + 1]).unwrap())),
+            None => unreachable!("the byte after the end of a PascalString must be null")
+        }
+    }
+
+    /// Consumes this `PascalString`, and returns its inner state as a `[u8; M]`, where the first bytes
+    /// store the length prefix, and the rest store the characters of the string. `M` must be equal to the
+    /// capacity of the string plus the width of the length prefix: for the default capacity, this is a
+    /// `[u8; 256]`.
     ///
     /// Note that if the string has been truncated, bytes beyond the end of the string will not have been
     /// zeroed.
+    ///
+    /// Using an `M` which is not equal to `N + L::WIDTH` is a compile time error.
     #[inline]
-    pub fn to_array(self) -> [u8; PASCAL_STRING_BUF_SIZE + 1] {
-        self.into()
+    pub fn to_array<const M: usize>(self) -> [u8; M] {
+        let () = ::ArrayLenCheck::<L, N, M>::VALID;
+        let mut array = [0u8; M];
+        L::write_len(self.len, &mut array);
+        for (byte, ch) in array[L::WIDTH..].iter_mut().zip(self.chars.iter()) {
+            *byte = ch.as_byte();
+        }
+        array
     }
 
     /// Sets the byte beyond the end of `len` to `AsciiChar::Null`, if this `PascalString` isn't full.
@@ -217,33 +650,62 @@ impl PascalString {
     #[inline]
     fn set_trailing_byte_to_null(&mut self) {
         if !self.is_full() {
-            self.chars[self.len as usize] = AsciiChar::Null;
+            self.chars[self.len()] = AsciiChar::Null;
+        }
+    }
+
+    /// Sets the length of the string, without touching the contents of the buffer.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+        self.len = L::from_usize(len);
+    }
+}
+
+impl<const N: usize> PascalString<N> {
+    /// Create a new `PascalString` from `s` in a constant expression.
+    ///
+    /// This is mostly useful for building `const` and `static` tables of strings. See also the `pstr!`
+    /// macro, which always evaluates this at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than the capacity of the string, or if it is not valid ascii. When this is
+    /// evaluated at compile time, the panic is reported as a compile error.
+    pub const fn from_literal(s: &str) -> Self {
+        let bytes = s.as_bytes();
+        assert!(bytes.len() <= N, "the literal is longer than the capacity of the PascalString");
+        let mut pstring = PascalString::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(bytes[i].is_ascii(), "the literal is not valid ascii");
+            pstring.chars[i] = AsciiChar::new(bytes[i] as char);
+            i += 1;
         }
+        pstring.len = bytes.len() as u8;
+        pstring
     }
 }
 
-impl Default for PascalString {
+impl<const N: usize, L: LengthPrefix> Default for PascalString<N, L> {
     #[inline]
     fn default() -> Self {
-        PascalString {
-            len: 0,
-            chars: [AsciiChar::Null; PASCAL_STRING_BUF_SIZE]
-        }
+        PascalString::new()
     }
 }
 
-impl Clone for PascalString {
+impl<const N: usize, L: LengthPrefix> Clone for PascalString<N, L> {
     fn clone(&self) -> Self {
-        let mut clone = PascalString::default();
-        clone.len = self.len;
-        unsafe {
-            ptr::copy_nonoverlapping(&self.chars, &mut clone.chars, PASCAL_STRING_BUF_SIZE);
+        PascalString {
+            len: self.len,
+            chars: self.chars
         }
-        clone
     }
 }
 
-impl AsciiExt for PascalString {
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+impl<const N: usize, L: LengthPrefix> AsciiExt for PascalString<N, L> {
     type Owned = Self;
 
     fn is_ascii(&self) -> bool {
@@ -263,25 +725,25 @@ impl AsciiExt for PascalString {
     }
 
     fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
-        self.chars().zip(other.chars()).all(|(c0, c1)| c0.eq_ignore_ascii_case(&c1))
+        self.chars().zip(other.chars()).all(|(c0, c1)| c0.eq_ignore_ascii_case(c1))
     }
 
     #[inline]
     fn make_ascii_uppercase(&mut self) {
-        for c in self.chars_mut() {
-            c.make_ascii_uppercase();
+        for ch in self.chars_mut() {
+            ch.make_ascii_uppercase();
         }
     }
 
     #[inline]
     fn make_ascii_lowercase(&mut self) {
-        for c in self.chars_mut() {
-            c.make_ascii_lowercase();
+        for ch in self.chars_mut() {
+            ch.make_ascii_lowercase();
         }
     }
 }
 
-impl fmt::Debug for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Debug for PascalString<N, L> {
     #[inline]
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         fmtr.debug_struct("PascalString")
@@ -291,14 +753,28 @@ impl fmt::Debug for PascalString {
     }
 }
 
-impl fmt::Display for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Display for PascalString<N, L> {
     #[inline]
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         fmtr.pad(self.as_ref())
     }
 }
 
-impl<S: AsRef<str> + ?Sized> PartialEq<S> for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Write for PascalString<N, L> {
+    /// Fails if there is no room left in the string, or `s` is not valid ascii, in which case the string is
+    /// not modified.
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.try_push(ch).map_err(|_| fmt::Error)
+    }
+}
+
+impl<S: AsRef<str> + ?Sized, const N: usize, L: LengthPrefix> PartialEq<S> for PascalString<N, L> {
     #[inline]
     fn eq(&self, other: &S) -> bool {
         let other = other.as_ref();
@@ -306,25 +782,25 @@ impl<S: AsRef<str> + ?Sized> PartialEq<S> for PascalString {
     }
 }
 
-impl Eq for PascalString { }
+impl<const N: usize, L: LengthPrefix> Eq for PascalString<N, L> { }
 
-impl<S: AsRef<str> + ?Sized> PartialOrd<S> for PascalString {
+impl<S: AsRef<str> + ?Sized, const N: usize, L: LengthPrefix> PartialOrd<S> for PascalString<N, L> {
     #[inline]
     fn partial_cmp(&self, other: &S) -> Option<Ordering> {
         let other = other.as_ref();
-        self.as_str().partial_cmp(&other)
+        self.as_str().partial_cmp(other)
     }
 }
 
-impl Ord for PascalString {
+impl<const N: usize, L: LengthPrefix> Ord for PascalString<N, L> {
     #[inline]
-    fn cmp(&self, other: &PascalString) -> Ordering {
+    fn cmp(&self, other: &PascalString<N, L>) -> Ordering {
         let other = other.as_ref();
         self.as_str().cmp(other)
     }
 }
 
-impl Deref for PascalString {
+impl<const N: usize, L: LengthPrefix> Deref for PascalString<N, L> {
     type Target = PascalStr;
     #[inline]
     fn deref(&self) -> &Self::Target {
@@ -335,7 +811,7 @@ impl Deref for PascalString {
     }
 }
 
-impl DerefMut for PascalString {
+impl<const N: usize, L: LengthPrefix> DerefMut for PascalString<N, L> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         let ascii_str: &mut [AsciiChar] = self.as_mut();
@@ -345,153 +821,169 @@ impl DerefMut for PascalString {
     }
 }
 
-impl AsRef<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<PascalStr> for PascalString<N, L> {
     fn as_ref(&self) -> &PascalStr {
         self.deref()
     }
 }
 
-impl AsRef<str> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<str> for PascalString<N, L> {
     fn as_ref(&self) -> &str {
         let bytes: &[u8] = self.as_ref();
         str::from_utf8(bytes).unwrap()
     }
 }
 
-impl AsRef<[u8]> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<[u8]> for PascalString<N, L> {
     fn as_ref(&self) -> &[u8] {
         unsafe {
-            slice::from_raw_parts(self.chars.as_ptr() as *const u8, self.len as usize)
+            slice::from_raw_parts(self.chars.as_ptr() as *const u8, L::to_usize(self.len))
         }
     }
 }
 
-impl AsRef<AsciiStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<AsciiStr> for PascalString<N, L> {
     fn as_ref(&self) -> &AsciiStr {
         let bytes: &[u8] = self.as_ref();
         AsciiStr::from_ascii(bytes).unwrap()
     }
 }
 
-impl AsRef<[AsciiChar]> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<[AsciiChar]> for PascalString<N, L> {
     fn as_ref(&self) -> &[AsciiChar] {
         unsafe {
-            slice::from_raw_parts(self.chars.as_ptr(), self.len as usize)
+            slice::from_raw_parts(self.chars.as_ptr(), L::to_usize(self.len))
         }
     }
 }
 
-impl AsMut<[AsciiChar]> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsMut<[AsciiChar]> for PascalString<N, L> {
     fn as_mut(&mut self) -> &mut [AsciiChar] {
         unsafe {
-            slice::from_raw_parts_mut(self.chars.as_mut_ptr(), self.len as usize)
+            slice::from_raw_parts_mut(self.chars.as_mut_ptr(), L::to_usize(self.len))
         }
     }
 }
 
-impl Borrow<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<PascalStr> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &PascalStr {
         self.deref()
     }
 }
 
-impl BorrowMut<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> BorrowMut<PascalStr> for PascalString<N, L> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut PascalStr {
         self.deref_mut()
     }
 }
 
-impl Borrow<str> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<str> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &str {
         self.as_ref()
     }
 }
 
-impl Borrow<[u8]> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<[u8]> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &[u8] {
         self.as_ref()
     }
 }
 
-impl Borrow<AsciiStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<AsciiStr> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &AsciiStr {
         self.as_ref()
     }
 }
 
-impl Borrow<[AsciiChar]> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<[AsciiChar]> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &[AsciiChar] {
         self.as_ref()
     }
 }
 
-impl BorrowMut<[AsciiChar]> for PascalString {
+impl<const N: usize, L: LengthPrefix> BorrowMut<[AsciiChar]> for PascalString<N, L> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut [AsciiChar] {
         self.as_mut()
     }
 }
 
-impl Into<[u8; PASCAL_STRING_BUF_SIZE + 1]> for PascalString {
-    fn into(self) -> [u8; PASCAL_STRING_BUF_SIZE + 1] {
-        let mut array = [0u8; PASCAL_STRING_BUF_SIZE + 1];
-        array[0] = self.len;
-        unsafe {
-            let chars_ptr = &self.chars as *const _ as *const [u8; PASCAL_STRING_BUF_SIZE];
-            let array_ptr = (&mut array as *mut _).offset(1) as *mut [u8; PASCAL_STRING_BUF_SIZE];
-            ptr::copy_nonoverlapping(chars_ptr, array_ptr, PASCAL_STRING_BUF_SIZE);
-        }
-        array
+impl From<PascalString> for [u8; PASCAL_STRING_BUF_SIZE + 1] {
+    #[inline]
+    fn from(string: PascalString) -> Self {
+        string.to_array()
     }
 }
 
-impl Into<String> for PascalString {
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for String {
     #[inline]
-    fn into(self) -> String {
-        String::from_utf8_lossy(self.as_ref()).into_owned()
+    fn from(string: PascalString<N, L>) -> Self {
+        String::from_utf8_lossy(string.as_ref()).into_owned()
     }
 }
 
-impl Into<Vec<u8>> for PascalString {
-    fn into(self) -> Vec<u8> {
-        let mut v = Vec::with_capacity(self.len());
-        v.extend_from_slice(self.as_ref());
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for Vec<u8> {
+    fn from(string: PascalString<N, L>) -> Self {
+        let mut v = Vec::with_capacity(string.len());
+        v.extend_from_slice(string.as_ref());
         v
     }
 }
 
-impl Into<Vec<AsciiChar>> for PascalString {
-    fn into(self) -> Vec<AsciiChar> {
-        let mut v = Vec::with_capacity(self.len());
-        v.extend_from_slice(self.as_ref());
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for Vec<AsciiChar> {
+    fn from(string: PascalString<N, L>) -> Self {
+        let mut v = Vec::with_capacity(string.len());
+        v.extend_from_slice(string.as_ref());
         v
     }
 }
 
-impl Into<AsciiString> for PascalString {
-    fn into(self) -> AsciiString {
-        AsciiString::from_ascii(self).unwrap()
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for AsciiString {
+    fn from(string: PascalString<N, L>) -> Self {
+        AsciiString::from_ascii(string).unwrap()
     }
 }
 
-impl FromStr for PascalString {
+impl<const N: usize, L: LengthPrefix> TryFrom<utf8::PascalString<N, L>> for PascalString<N, L> {
+    type Error = AsciiError;
+
+    /// Converts a `utf8::PascalString` with the same capacity and length prefix into an ascii `PascalString`.
+    ///
+    /// Returns an `Err` if the string contains any non-ascii characters. `AsciiError::valid_up_to()` gives the
+    /// byte index of the first one.
+    #[inline]
+    fn try_from(string: utf8::PascalString<N, L>) -> Result<Self, AsciiError> {
+        let ascii = AsciiStr::from_ascii(string.as_str())?;
+        let mut pstring = PascalString::new();
+        pstring.chars[..ascii.len()].copy_from_slice(ascii.as_slice());
+        pstring.set_len(ascii.len());
+        pstring.set_trailing_byte_to_null();
+        Ok(pstring)
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> FromStr for PascalString<N, L> {
     type Err = PascalStringCreateError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         PascalString::from(s)
     }
 }
 
-impl FromIterator<AsciiChar> for PascalString {
+impl<const N: usize, L: LengthPrefix> FromIterator<AsciiChar> for PascalString<N, L> {
     fn from_iter<I: IntoIterator<Item = AsciiChar>>(iter: I) -> Self {
-        let mut iter = iter.into_iter();
+        let iter = iter.into_iter();
         let mut pstring = PascalString::new();
-        while let Some(ch) = iter.next() {
+        for ch in iter {
             // We know that the characters are valid ascii, and it's probably kinder to drop characters
             // past the 255th index than panic in this method.
             let _ = pstring.try_push(ch);
@@ -500,9 +992,9 @@ impl FromIterator<AsciiChar> for PascalString {
     }
 }
 
-impl IntoIterator for PascalString {
+impl<const N: usize, L: LengthPrefix> IntoIterator for PascalString<N, L> {
     type Item = AsciiChar;
-    type IntoIter = IntoChars;
+    type IntoIter = IntoChars<N, L>;
     fn into_iter(self) -> Self::IntoIter {
         IntoChars(self)
     }
@@ -510,9 +1002,9 @@ impl IntoIterator for PascalString {
 
 /// An iterator over the buffer of a `PascalString`. Has ownership of the iterated `PascalString`.
 #[derive(Debug)]
-pub struct IntoChars(PascalString);
+pub struct IntoChars<const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8>(PascalString<N, L>);
 
-impl Iterator for IntoChars {
+impl<const N: usize, L: LengthPrefix> Iterator for IntoChars<N, L> {
     type Item = AsciiChar;
     fn next(&mut self) -> Option<Self::Item> {
         if !self.0.is_empty() {
@@ -523,39 +1015,199 @@ impl Iterator for IntoChars {
     }
 }
 
-impl ExactSizeIterator for IntoChars {
+impl<const N: usize, L: LengthPrefix> ExactSizeIterator for IntoChars<N, L> {
     fn len(&self) -> usize {
         self.0.len()
     }
 }
 
+/// Returns the ascii approximation of a Latin-1 character which is not ascii, if it has one.
+fn transliterate(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        '\u{a0}' => " ",
+        '¡' => "!",
+        '«' => "<<",
+        '»' => ">>",
+        '¿' => "?",
+        '×' => "x",
+        'À'..='Å' => "A",
+        'Æ' => "AE",
+        'Ç' => "C",
+        'È'..='Ë' => "E",
+        'Ì'..='Ï' => "I",
+        'Ð' => "D",
+        'Ñ' => "N",
+        'Ò'..='Ö' | 'Ø' => "O",
+        'Ù'..='Ü' => "U",
+        'Ý' => "Y",
+        'Þ' => "TH",
+        'ß' => "ss",
+        'à'..='å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è'..='ë' => "e",
+        'ì'..='ï' => "i",
+        'ð' => "d",
+        'ñ' => "n",
+        'ò'..='ö' | 'ø' => "o",
+        'ù'..='ü' => "u",
+        'ý' | 'ÿ' => "y",
+        'þ' => "th",
+        _ => return None
+    })
+}
+
+/// A draining iterator over a range of characters in a `PascalString`.
+///
+/// This is created by `PascalString::drain()`.
+#[derive(Debug)]
+pub struct Drain<'a, const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
+    string: &'a mut PascalString<N, L>,
+    /// The range which is removed when the iterator is dropped.
+    start: usize,
+    end: usize,
+    /// The range which has not been yielded yet.
+    front: usize,
+    back: usize
+}
+
+impl<'a, const N: usize, L: LengthPrefix> Drain<'a, N, L> {
+    /// Returns the characters which have not been yielded yet.
+    #[inline]
+    pub fn as_str(&self) -> &AsciiStr {
+        From::from(&self.string.chars[self.front..self.back])
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> Iterator for Drain<'a, N, L> {
+    type Item = AsciiChar;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.string.chars[self.front - 1])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> DoubleEndedIterator for Drain<'a, N, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.string.chars[self.back])
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> ExactSizeIterator for Drain<'a, N, L> { }
+
+impl<'a, const N: usize, L: LengthPrefix> Drop for Drain<'a, N, L> {
+    fn drop(&mut self) {
+        let len = self.string.len();
+        self.string.chars.copy_within(self.end..len, self.start);
+        self.string.set_len(len - (self.end - self.start));
+        self.string.set_trailing_byte_to_null();
+    }
+}
+
+/// Adapts a `PascalString` to `fmt::Write` for `PascalString::format()`, keeping hold of the error which
+/// stopped the formatting.
+struct FormatWriter<const N: usize, L: LengthPrefix> {
+    string: PascalString<N, L>,
+    truncate: bool,
+    /// The length of the formatted text so far, which can be longer than the string.
+    required: usize,
+    error: Option<PascalStringAppendError>
+}
+
+impl<const N: usize, L: LengthPrefix> fmt::Write for FormatWriter<N, L> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.required += s.len();
+        if self.required > N && !self.truncate {
+            // Measure the rest of the text without storing it, so that the error reports its full length.
+            return Ok(());
+        }
+        let result = if self.truncate {
+            self.string.push_str_truncated(s)
+        } else {
+            self.string.try_push_str(s)
+        };
+        result.map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
 /// Indicates the range of errors which can occur from creating a new `PascalString`.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PascalStringCreateError {
     /// The data provided to the constructor was larger than the `PascalString` could store.
-    InputTooLong,
+    InputTooLong {
+        /// The number of characters in the data.
+        required: usize,
+        /// The capacity of the string.
+        available: usize
+    },
     /// The data provided was not correctly encoded as ascii.
     NotValidAscii(AsciiError)
 }
 
+impl PascalStringCreateError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringCreateError::InputTooLong { .. } => "the input data is longer than what a PascalString can store",
+            PascalStringCreateError::NotValidAscii(_) =>"could not convert input data to ascii"
+        }
+    }
+
+    /// Returns the number of bytes at the start of the input which could have been stored: the capacity of the
+    /// string if the input was too long, or the offset of the first character which is not ascii.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        match *self {
+            PascalStringCreateError::InputTooLong { available, .. } => available,
+            PascalStringCreateError::NotValidAscii(ref e) => e.valid_up_to()
+        }
+    }
+
+    /// Returns the part of `input`, which must be the input that caused this error, from `valid_up_to()`
+    /// onwards.
+    #[inline]
+    pub fn unconsumed<'a, B: AsRef<[u8]> + ?Sized>(&self, input: &'a B) -> &'a [u8] {
+        let input = input.as_ref();
+        &input[cmp::min(self.valid_up_to(), input.len())..]
+    }
+}
+
 impl fmt::Display for PascalStringCreateError {
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            PascalStringCreateError::InputTooLong => fmtr.pad(self.description()),
-            PascalStringCreateError::NotValidAscii(ref e) => write!(fmtr, "{}: {}", self.description(), e)
+            PascalStringCreateError::InputTooLong { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            PascalStringCreateError::NotValidAscii(ref e) => write!(fmtr, "{}: {}", self.message(), e)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for PascalStringCreateError {
     fn description(&self) -> &str {
-        match *self {
-            PascalStringCreateError::InputTooLong => "the input data is longer than what a PascalString can store",
-            PascalStringCreateError::NotValidAscii(_) =>"could not convert input data to ascii"
-        }
+        self.message()
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         if let PascalStringCreateError::NotValidAscii(ref e) = *self {
             Some(e)
         } else {
@@ -572,32 +1224,65 @@ impl<E: Into<AsciiError>> From<E> for PascalStringCreateError {
 }
 
 /// Indicates the range of errors which can occur from appending string data to a `PascalString`.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PascalStringAppendError {
     /// There is no room to store the appended data.
-    NoRoom,
+    NoRoom {
+        /// The number of characters needed to store the appended data.
+        required: usize,
+        /// The number of characters which were left in the string.
+        available: usize
+    },
     /// The data provided was not correctly encoded as ascii.
     NotValidAscii(AsciiError)
 }
 
+impl PascalStringAppendError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringAppendError::NoRoom { .. } => "there is no space left in the string to append the data",
+            PascalStringAppendError::NotValidAscii(_) =>"could not convert string to ascii"
+        }
+    }
+
+    /// Returns the number of bytes at the start of the appended data which could have been stored: the room
+    /// which was left in the string if there was not enough, or the offset of the first character which is
+    /// not ascii.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        match *self {
+            PascalStringAppendError::NoRoom { available, .. } => available,
+            PascalStringAppendError::NotValidAscii(ref e) => e.valid_up_to()
+        }
+    }
+
+    /// Returns the part of `input`, which must be the appended data that caused this error, from
+    /// `valid_up_to()` onwards.
+    #[inline]
+    pub fn unconsumed<'a, B: AsRef<[u8]> + ?Sized>(&self, input: &'a B) -> &'a [u8] {
+        let input = input.as_ref();
+        &input[cmp::min(self.valid_up_to(), input.len())..]
+    }
+}
+
 impl fmt::Display for PascalStringAppendError {
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            PascalStringAppendError::NoRoom => fmtr.pad(self.description()),
-            PascalStringAppendError::NotValidAscii(ref e) => write!(fmtr, "{}: {}", self.description(), e)
+            PascalStringAppendError::NoRoom { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            PascalStringAppendError::NotValidAscii(ref e) => write!(fmtr, "{}: {}", self.message(), e)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for PascalStringAppendError {
     fn description(&self) -> &str {
-        match *self {
-            PascalStringAppendError::NoRoom => "there is no space left in the string to append the data",
-            PascalStringAppendError::NotValidAscii(_) =>"could not convert string to ascii"
-        }
+        self.message()
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         if let PascalStringAppendError::NotValidAscii(ref e) = *self {
             Some(e)
         } else {
@@ -614,7 +1299,7 @@ impl<E: Into<AsciiError>> From<E> for PascalStringAppendError {
 }
 
 /// An error type which abstracts over ascii conversion errors.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AsciiError {
     /// A character was not encoded as ascii.
     Char(ToAsciiCharError),
@@ -622,24 +1307,41 @@ pub enum AsciiError {
     Str(AsAsciiStrError)
 }
 
+impl AsciiError {
+    fn message(&self) -> &'static str {
+        match *self {
+            AsciiError::Char(_) => "could not convert character to ascii",
+            AsciiError::Str(_) =>"could not convert string to ascii"
+        }
+    }
+
+    /// Returns the byte offset of the first character which is not ascii. This is always `0` for a single
+    /// character.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        match *self {
+            AsciiError::Char(_) => 0,
+            AsciiError::Str(ref e) => e.valid_up_to()
+        }
+    }
+}
+
 impl fmt::Display for AsciiError {
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            AsciiError::Char(ref e) => write!(fmtr, "{}: {}", self.description(), e),
-            AsciiError::Str(ref e) => write!(fmtr, "{}: {}", self.description(), e)
+            AsciiError::Char(ref e) => write!(fmtr, "{}: {}", self.message(), e),
+            AsciiError::Str(ref e) => write!(fmtr, "{}: {}", self.message(), e)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for AsciiError {
     fn description(&self) -> &str {
-        match *self {
-            AsciiError::Char(_) => "could not convert character to ascii: {}",
-            AsciiError::Str(_) =>"could not convert string to ascii: {}"
-        }
+        self.message()
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         match *self {
             AsciiError::Char(ref e) => Some(e),
             AsciiError::Str(ref e) => Some(e)
@@ -0,0 +1,219 @@
+use ascii_crate::{AsciiChar, AsciiStr};
+use core::iter::Iterator;
+use ::ascii::PascalStr;
+
+/// A pattern which can be searched for in an `ascii::PascalStr`.
+///
+/// This is implemented for:
+///
+/// * Single characters: `AsciiChar`, `char` and `u8`.
+/// * Sets of characters, where any character in the set matches: `&[AsciiChar]`, `&[char]` and `&[u8]`, along
+///   with references to arrays of these types. Note that this means that `b"ab"` matches either `a` or `b`,
+///   rather than the substring `ab`.
+/// * Substrings: `&str`, `&AsciiStr` and `&PascalStr`.
+/// * Predicates: `FnMut(AsciiChar) -> bool`.
+///
+/// Characters which are not ascii never match.
+pub trait Pattern {
+    /// If this pattern matches `haystack` starting at `index`, returns the number of characters in the match.
+    ///
+    /// `index` may be equal to the length of `haystack`, in which case only an empty match is possible.
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize>;
+}
+
+impl Pattern for AsciiChar {
+    #[inline]
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+        match haystack.as_slice().get(index) {
+            Some(ch) if ch == self => Some(1),
+            _ => None
+        }
+    }
+}
+
+impl Pattern for char {
+    #[inline]
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+        match haystack.as_slice().get(index) {
+            Some(ch) if ch.as_char() == *self => Some(1),
+            _ => None
+        }
+    }
+}
+
+impl Pattern for u8 {
+    #[inline]
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+        match haystack.as_slice().get(index) {
+            Some(ch) if ch.as_byte() == *self => Some(1),
+            _ => None
+        }
+    }
+}
+
+macro_rules! impl_set_pattern {
+    ($($set:ty => $to_elem:ident),*) => {$(
+        impl<'b> Pattern for &'b [$set] {
+            #[inline]
+            fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+                match haystack.as_slice().get(index) {
+                    Some(ch) if self.contains(&ch.$to_elem()) => Some(1),
+                    _ => None
+                }
+            }
+        }
+
+        impl<'b, const M: usize> Pattern for &'b [$set; M] {
+            #[inline]
+            fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+                (&self[..]).match_at(haystack, index)
+            }
+        }
+    )*}
+}
+
+impl_set_pattern!(AsciiChar => clone, char => as_char, u8 => as_byte);
+
+macro_rules! impl_substring_pattern {
+    ($($substring:ty => $as_bytes:ident),*) => {$(
+        impl<'b> Pattern for &'b $substring {
+            #[inline]
+            fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+                let needle = self.$as_bytes();
+                if haystack.as_bytes()[index..].starts_with(needle) {
+                    Some(needle.len())
+                } else {
+                    None
+                }
+            }
+        }
+    )*}
+}
+
+impl_substring_pattern!(str => as_bytes, AsciiStr => as_bytes);
+
+impl Pattern for &PascalStr {
+    #[inline]
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+        self.as_str().match_at(haystack, index)
+    }
+}
+
+impl<F: FnMut(AsciiChar) -> bool> Pattern for F {
+    #[inline]
+    fn match_at(&mut self, haystack: &AsciiStr, index: usize) -> Option<usize> {
+        match haystack.as_slice().get(index) {
+            Some(&ch) if self(ch) => Some(1),
+            _ => None
+        }
+    }
+}
+
+/// Finds the non-overlapping matches of a pattern in a string, from either end.
+#[derive(Clone, Debug)]
+pub(crate) struct Searcher<'a, P> {
+    haystack: &'a AsciiStr,
+    pattern: P,
+    /// The next index to try from the front.
+    position: usize,
+    /// Matches found from the back must end at or before this index.
+    back: usize,
+    /// The next index to try from the back, or `None` if the start of the string has been passed.
+    back_position: Option<usize>
+}
+
+impl<'a, P: Pattern> Searcher<'a, P> {
+    #[inline]
+    pub(crate) fn new(haystack: &'a AsciiStr, pattern: P) -> Self {
+        Searcher {
+            haystack: haystack,
+            pattern: pattern,
+            position: 0,
+            back: haystack.len(),
+            back_position: Some(haystack.len())
+        }
+    }
+
+    #[inline]
+    pub(crate) fn haystack(&self) -> &'a AsciiStr {
+        self.haystack
+    }
+
+    /// Find the next match from the front, returning the index and length of the match.
+    pub(crate) fn next_match(&mut self) -> Option<(usize, usize)> {
+        while self.position <= self.haystack.len() {
+            let start = self.position;
+            if let Some(len) = self.pattern.match_at(self.haystack, start) {
+                // An empty match would otherwise be found at the same index forever.
+                self.position = start + ::core::cmp::max(len, 1);
+                return Some((start, len));
+            }
+            self.position += 1;
+        }
+        None
+    }
+
+    /// Find the next match from the back, returning the index and length of the match.
+    pub(crate) fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        while let Some(start) = self.back_position {
+            self.back_position = start.checked_sub(1);
+            match self.pattern.match_at(self.haystack, start) {
+                Some(len) if start + len <= self.back => {
+                    self.back = start;
+                    if len > 0 {
+                        self.back_position = Some(start);
+                    }
+                    return Some((start, len));
+                }
+                _ => ()
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the non-overlapping matches of a pattern in a `PascalStr`, and their indices.
+///
+/// This is created by `PascalStr::match_indices()`.
+#[derive(Clone, Debug)]
+pub struct MatchIndices<'a, P>(Searcher<'a, P>);
+
+impl<'a, P: Pattern> MatchIndices<'a, P> {
+    #[inline]
+    pub(crate) fn new(haystack: &'a AsciiStr, pattern: P) -> Self {
+        MatchIndices(Searcher::new(haystack, pattern))
+    }
+}
+
+impl<'a, P: Pattern> Iterator for MatchIndices<'a, P> {
+    type Item = (usize, &'a PascalStr);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let haystack = self.0.haystack();
+        self.0.next_match().map(|(start, len)| (start, From::from(&haystack[start..start + len])))
+    }
+}
+
+/// An iterator over the non-overlapping matches of a pattern in a `PascalStr`.
+///
+/// This is created by `PascalStr::matches()`.
+#[derive(Clone, Debug)]
+pub struct Matches<'a, P>(Searcher<'a, P>);
+
+impl<'a, P: Pattern> Matches<'a, P> {
+    #[inline]
+    pub(crate) fn new(haystack: &'a AsciiStr, pattern: P) -> Self {
+        Matches(Searcher::new(haystack, pattern))
+    }
+}
+
+impl<'a, P: Pattern> Iterator for Matches<'a, P> {
+    type Item = &'a PascalStr;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let haystack = self.0.haystack();
+        self.0.next_match().map(|(start, len)| From::from(&haystack[start..start + len]))
+    }
+}
@@ -0,0 +1,3 @@
+serde_impls!(ascii, from, "Fails with the message of a `PascalStringCreateError` if the input is longer than the \
+                           capacity of the string, or is not valid ascii.",
+             "an ascii string of at most {} characters");
@@ -0,0 +1,2 @@
+split_iterators!(ascii, ::ascii_crate::AsciiStr, ::ascii_crate::AsciiChar, is_ascii_whitespace,
+                 "An iterator over the non-empty parts of a `PascalStr` separated by ascii whitespace.");
@@ -0,0 +1,233 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// A single byte character encoding, which maps each byte to a character.
+///
+/// Decoding is lossless: every byte decodes to exactly one character, and encoding that character gives back
+/// the same byte. Encoding a `char` fails if the character has no byte in this encoding.
+///
+/// The types in this module are used as the `E` parameter of `codepage::PascalString` and
+/// `codepage::PascalStr`.
+pub trait Encoding {
+    /// The name of this encoding, used in error messages.
+    const NAME: &'static str;
+
+    /// Decode a single byte into the character it represents.
+    fn decode(byte: u8) -> char;
+
+    /// Encode a character as a single byte, or return `None` if it cannot be represented in this encoding.
+    fn encode(ch: char) -> Option<u8>;
+}
+
+/// ISO 8859-1, where each byte is the Unicode code point of the same value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Latin1;
+
+/// Windows code page 1252, the default encoding of Delphi and of Western European versions of Windows.
+///
+/// This is Latin-1 with printable characters in place of most of the C1 control codes. The five bytes which
+/// Windows leaves undefined (`0x81`, `0x8D`, `0x8F`, `0x90` and `0x9D`) decode to the C1 control codes of the
+/// same value, so that every byte can be decoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Windows1252;
+
+/// Mac OS Roman, the encoding of `Str255` strings on classic Mac OS.
+///
+/// Byte `0xDB` decodes to the euro sign, as in Mac OS 8.5 and later, and `0xF0` decodes to the Apple logo at
+/// `U+F8FF` in the private use area.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacRoman;
+
+/// IBM code page 437, the character set of the original IBM PC and of DOS programs such as Turbo Pascal.
+///
+/// Bytes below `0x80` are decoded as ascii, including the control codes, rather than as the graphical symbols
+/// which the IBM PC displayed for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cp437;
+
+impl Encoding for Latin1 {
+    const NAME: &'static str = "Latin-1";
+
+    #[inline]
+    fn decode(byte: u8) -> char {
+        byte as char
+    }
+
+    #[inline]
+    fn encode(ch: char) -> Option<u8> {
+        if (ch as u32) < 0x100 {
+            Some(ch as u8)
+        } else {
+            None
+        }
+    }
+}
+
+/// The characters of bytes `0x80` to `0x9F` in Windows-1252. The rest of the code page matches Latin-1.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Encoding for Windows1252 {
+    const NAME: &'static str = "Windows-1252";
+
+    #[inline]
+    fn decode(byte: u8) -> char {
+        match byte {
+            0x80..=0x9F => WINDOWS_1252[byte as usize - 0x80],
+            _ => byte as char
+        }
+    }
+
+    #[inline]
+    fn encode(ch: char) -> Option<u8> {
+        match ch as u32 {
+            0x00..=0x7F | 0xA0..=0xFF => Some(ch as u8),
+            _ => WINDOWS_1252.iter().position(|&c| c == ch).map(|i| i as u8 + 0x80)
+        }
+    }
+}
+
+/// The characters of bytes `0x80` to `0xFF` in Mac OS Roman.
+const MAC_ROMAN: [char; 128] = [
+    '\u{00C4}', '\u{00C5}', '\u{00C7}', '\u{00C9}', '\u{00D1}', '\u{00D6}', '\u{00DC}', '\u{00E1}',
+    '\u{00E0}', '\u{00E2}', '\u{00E4}', '\u{00E3}', '\u{00E5}', '\u{00E7}', '\u{00E9}', '\u{00E8}',
+    '\u{00EA}', '\u{00EB}', '\u{00ED}', '\u{00EC}', '\u{00EE}', '\u{00EF}', '\u{00F1}', '\u{00F3}',
+    '\u{00F2}', '\u{00F4}', '\u{00F6}', '\u{00F5}', '\u{00FA}', '\u{00F9}', '\u{00FB}', '\u{00FC}',
+    '\u{2020}', '\u{00B0}', '\u{00A2}', '\u{00A3}', '\u{00A7}', '\u{2022}', '\u{00B6}', '\u{00DF}',
+    '\u{00AE}', '\u{00A9}', '\u{2122}', '\u{00B4}', '\u{00A8}', '\u{2260}', '\u{00C6}', '\u{00D8}',
+    '\u{221E}', '\u{00B1}', '\u{2264}', '\u{2265}', '\u{00A5}', '\u{00B5}', '\u{2202}', '\u{2211}',
+    '\u{220F}', '\u{03C0}', '\u{222B}', '\u{00AA}', '\u{00BA}', '\u{03A9}', '\u{00E6}', '\u{00F8}',
+    '\u{00BF}', '\u{00A1}', '\u{00AC}', '\u{221A}', '\u{0192}', '\u{2248}', '\u{2206}', '\u{00AB}',
+    '\u{00BB}', '\u{2026}', '\u{00A0}', '\u{00C0}', '\u{00C3}', '\u{00D5}', '\u{0152}', '\u{0153}',
+    '\u{2013}', '\u{2014}', '\u{201C}', '\u{201D}', '\u{2018}', '\u{2019}', '\u{00F7}', '\u{25CA}',
+    '\u{00FF}', '\u{0178}', '\u{2044}', '\u{20AC}', '\u{2039}', '\u{203A}', '\u{FB01}', '\u{FB02}',
+    '\u{2021}', '\u{00B7}', '\u{201A}', '\u{201E}', '\u{2030}', '\u{00C2}', '\u{00CA}', '\u{00C1}',
+    '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}', '\u{00CC}', '\u{00D3}', '\u{00D4}',
+    '\u{F8FF}', '\u{00D2}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{0131}', '\u{02C6}', '\u{02DC}',
+    '\u{00AF}', '\u{02D8}', '\u{02D9}', '\u{02DA}', '\u{00B8}', '\u{02DD}', '\u{02DB}', '\u{02C7}',
+];
+
+impl Encoding for MacRoman {
+    const NAME: &'static str = "Mac OS Roman";
+
+    #[inline]
+    fn decode(byte: u8) -> char {
+        decode_high_half(&MAC_ROMAN, byte)
+    }
+
+    #[inline]
+    fn encode(ch: char) -> Option<u8> {
+        encode_high_half(&MAC_ROMAN, ch)
+    }
+}
+
+/// The characters of bytes `0x80` to `0xFF` in code page 437.
+const CP437: [char; 128] = [
+    '\u{00C7}', '\u{00FC}', '\u{00E9}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E5}', '\u{00E7}',
+    '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00EF}', '\u{00EE}', '\u{00EC}', '\u{00C4}', '\u{00C5}',
+    '\u{00C9}', '\u{00E6}', '\u{00C6}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00FB}', '\u{00F9}',
+    '\u{00FF}', '\u{00D6}', '\u{00DC}', '\u{00A2}', '\u{00A3}', '\u{00A5}', '\u{20A7}', '\u{0192}',
+    '\u{00E1}', '\u{00ED}', '\u{00F3}', '\u{00FA}', '\u{00F1}', '\u{00D1}', '\u{00AA}', '\u{00BA}',
+    '\u{00BF}', '\u{2310}', '\u{00AC}', '\u{00BD}', '\u{00BC}', '\u{00A1}', '\u{00AB}', '\u{00BB}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{2561}', '\u{2562}', '\u{2556}',
+    '\u{2555}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255D}', '\u{255C}', '\u{255B}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252C}', '\u{251C}', '\u{2500}', '\u{253C}', '\u{255E}', '\u{255F}',
+    '\u{255A}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256C}', '\u{2567}',
+    '\u{2568}', '\u{2564}', '\u{2565}', '\u{2559}', '\u{2558}', '\u{2552}', '\u{2553}', '\u{256B}',
+    '\u{256A}', '\u{2518}', '\u{250C}', '\u{2588}', '\u{2584}', '\u{258C}', '\u{2590}', '\u{2580}',
+    '\u{03B1}', '\u{00DF}', '\u{0393}', '\u{03C0}', '\u{03A3}', '\u{03C3}', '\u{00B5}', '\u{03C4}',
+    '\u{03A6}', '\u{0398}', '\u{03A9}', '\u{03B4}', '\u{221E}', '\u{03C6}', '\u{03B5}', '\u{2229}',
+    '\u{2261}', '\u{00B1}', '\u{2265}', '\u{2264}', '\u{2320}', '\u{2321}', '\u{00F7}', '\u{2248}',
+    '\u{00B0}', '\u{2219}', '\u{00B7}', '\u{221A}', '\u{207F}', '\u{00B2}', '\u{25A0}', '\u{00A0}',
+];
+
+impl Encoding for Cp437 {
+    const NAME: &'static str = "code page 437";
+
+    #[inline]
+    fn decode(byte: u8) -> char {
+        decode_high_half(&CP437, byte)
+    }
+
+    #[inline]
+    fn encode(ch: char) -> Option<u8> {
+        encode_high_half(&CP437, ch)
+    }
+}
+
+/// Decode a byte of an encoding which matches ascii below `0x80`, and uses `table` for the bytes above it.
+#[inline]
+fn decode_high_half(table: &[char; 128], byte: u8) -> char {
+    if byte < 0x80 {
+        byte as char
+    } else {
+        table[byte as usize - 0x80]
+    }
+}
+
+/// Encode a character in an encoding which matches ascii below `0x80`, and uses `table` for the bytes above it.
+#[inline]
+fn encode_high_half(table: &[char; 128], ch: char) -> Option<u8> {
+    if (ch as u32) < 0x80 {
+        Some(ch as u8)
+    } else {
+        table.iter().position(|&c| c == ch).map(|i| i as u8 + 0x80)
+    }
+}
+
+/// The error returned when a character cannot be represented in an `Encoding`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodeError {
+    character: char,
+    index: usize,
+    encoding: &'static str
+}
+
+impl EncodeError {
+    #[inline]
+    pub(crate) fn new<E: Encoding>(character: char, index: usize) -> Self {
+        EncodeError {
+            character: character,
+            index: index,
+            encoding: E::NAME
+        }
+    }
+
+    /// Returns the character which could not be encoded.
+    #[inline]
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    /// Returns the byte index of the character in the `str` which was being encoded. All of the characters
+    /// before it could be encoded.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the name of the encoding which could not represent the character.
+    #[inline]
+    pub fn encoding(&self) -> &'static str {
+        self.encoding
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "the character {:?} at index {} cannot be encoded in {}", self.character, self.index,
+               self.encoding)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EncodeError {
+    fn description(&self) -> &str {
+        "the character cannot be encoded"
+    }
+}
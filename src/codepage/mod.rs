@@ -0,0 +1,115 @@
+mod encoding;
+mod pascal_str;
+mod pascal_string;
+
+pub use self::encoding::{Cp437, EncodeError, Encoding, Latin1, MacRoman, Windows1252};
+pub use self::pascal_str::{Chars, PascalStr};
+pub use self::pascal_string::{PascalString, PascalStringAppendError, PascalStringCreateError};
+pub use self::pascal_string::{Cp437String, Latin1String, MacRomanString, Windows1252String};
+pub use ::ascii::InteriorNullError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use std::ffi::CStr;
+    use std::prelude::v1::*;
+
+    fn assert_lossless<E: Encoding>() {
+        for byte in 0..=255u8 {
+            assert_eq!(E::encode(E::decode(byte)), Some(byte), "byte {:#x} in {}", byte, E::NAME);
+        }
+    }
+
+    #[test]
+    fn test_decoding_is_lossless() {
+        assert_lossless::<Latin1>();
+        assert_lossless::<Windows1252>();
+        assert_lossless::<MacRoman>();
+        assert_lossless::<Cp437>();
+
+        assert_eq!(Windows1252::decode(0x80), '€');
+        assert_eq!(Windows1252::decode(0x81), '\u{81}');
+        assert_eq!(Windows1252::decode(0xE9), 'é');
+        assert_eq!(MacRoman::decode(0x8E), 'é');
+        assert_eq!(Cp437::decode(0x82), 'é');
+        assert_eq!(Cp437::decode(0xC9), '╔');
+        assert_eq!(Latin1::encode('€'), None);
+        assert_eq!(Cp437::encode('€'), None);
+    }
+
+    #[test]
+    fn test_string_creation() {
+        let string = Windows1252String::<15>::from_str("“Größe” €5").unwrap();
+        assert_eq!(string.len(), 10);
+        assert_eq!(string.as_bytes(), b"\x93Gr\xf6\xdfe\x94 \x805");
+        assert_eq!(string, "“Größe” €5");
+        assert_eq!(string[0u8], 0x93);
+        assert_eq!(string.get(3), Some('ö'));
+        assert_eq!(string.chars().next_back(), Some('5'));
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.to_string(), "“Größe” €5");
+        #[cfg(feature = "alloc")]
+        assert_eq!(format!("{:?}", &string[1..6]), "\"Größe\"");
+
+        let bytes = Cp437String::<15>::from_bytes(b"\xc9\xcd\xbb").unwrap();
+        assert_eq!(bytes, "╔═╗");
+        assert_eq!(Cp437String::<2>::from_bytes(b"\xc9\xcd\xbb"),
+                   Err(PascalStringCreateError::InputTooLong { required: 3, available: 2 }));
+        assert_eq!(Latin1String::<3>::from_str("señor"),
+                   Err(PascalStringCreateError::InputTooLong { required: 5, available: 3 }));
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let error = Latin1String::<15>::from_str("naïve → smart").unwrap_err();
+        let error = match error {
+            PascalStringCreateError::Unencodable(e) => e,
+            e => unreachable!("unexpected error {:?}", e)
+        };
+        assert_eq!(error.character(), '→');
+        assert_eq!(error.index(), 7);
+        assert_eq!(error.encoding(), "Latin-1");
+
+        let mut string = Latin1String::<15>::from_str("naïve").unwrap();
+        match string.try_push_str(" → ") {
+            Err(PascalStringAppendError::Unencodable(e)) => assert_eq!((e.character(), e.index()), ('→', 1)),
+            result => unreachable!("unexpected result {:?}", result)
+        }
+        assert_eq!(string, "naïve");
+        assert!(string.try_push('→').is_err());
+        string.push('!');
+        assert_eq!(string, "naïve!");
+        assert_eq!(string.pop(), Some('!'));
+
+        let (lossy, loss) = Latin1String::<7>::from_lossy("a → b → c", b'?');
+        assert_eq!(lossy, "a ? b ?");
+        assert_eq!((loss.replaced(), loss.truncated()), (2, 2));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_as_cstr() {
+        let mut string = MacRomanString::<4>::from_str("Café").unwrap();
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), b"Caf\x8e");
+        string.truncate(2);
+        assert_eq!(string.as_cstr().unwrap(), CStr::from_bytes_with_nul(b"Ca\0").unwrap());
+        string.push('\0');
+        string.push('e');
+        assert_eq!(string.as_cstr().unwrap_err().interior_null_index(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_and_write() {
+        use ::Layout;
+
+        let string = MacRomanString::<15>::from_str("Ærø").unwrap();
+        let mut buffer = Vec::new();
+        string.write_to(&mut buffer, Layout::Compact).unwrap();
+        assert_eq!(buffer, b"\x03\xaer\xbf");
+        let read = MacRomanString::<15>::read_from(&mut &buffer[..], Layout::Compact).unwrap();
+        assert_eq!(read, string);
+        assert_eq!(string.to_array::<16>()[..4], buffer[..]);
+    }
+}
@@ -0,0 +1,288 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::ffi::CString;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::fmt::{self, Write};
+use core::hash::{Hash, Hasher};
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, Iterator};
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeTo};
+use core::slice::Iter;
+#[cfg(feature = "alloc")]
+use ::ascii::InteriorNullError;
+use ::codepage::Encoding;
+
+/// A borrowed slice from a `PascalString`. Does not own its data.
+///
+/// Each byte of the string is a single character in the encoding `E`.
+#[repr(transparent)]
+pub struct PascalStr<E> {
+    encoding: PhantomData<E>,
+    /// The encoded bytes, borrowed from the original `PascalString`
+    bytes: [u8]
+}
+
+impl<E: Encoding> PascalStr<E> {
+    /// Borrow `bytes` as a `PascalStr`. As every byte is a valid character, this cannot fail.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> &PascalStr<E> {
+        unsafe { &*(bytes as *const [u8] as *const PascalStr<E>) }
+    }
+
+    /// Mutably borrow `bytes` as a `PascalStr`.
+    #[inline]
+    pub fn from_bytes_mut(bytes: &mut [u8]) -> &mut PascalStr<E> {
+        unsafe { &mut *(bytes as *mut [u8] as *mut PascalStr<E>) }
+    }
+
+    /// Get a pointer to the first byte of the string buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.bytes.as_ptr()
+    }
+
+    /// Get the encoded bytes of the `PascalStr`.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Get the encoded bytes of the `PascalStr` mutably. Any byte is a valid character, so this is safe.
+    #[inline]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+
+    /// Get this string as a `CStr`.
+    ///
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If the last character of
+    /// this string is not a null character, then a new `CString` will be allocated to hold the trailing null
+    /// byte.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        match self.bytes.iter().position(|&b| b == 0) {
+            Some(pos) if pos != (self.len() - 1) => Err(InteriorNullError::new(pos)),
+            Some(_) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(&self.bytes).unwrap())),
+            None => Ok(Cow::Owned(CString::new(&self.bytes).unwrap()))
+        }
+    }
+
+    /// Returns the number of characters in the string, which is also the number of bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns true if the string has a length of 0
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Decode the character at `index`, or return `None` if `index` is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<char> {
+        self.bytes.get(index).map(|&b| E::decode(b))
+    }
+
+    /// Get an iterator over the decoded characters of the string.
+    #[inline]
+    pub fn chars(&self) -> Chars<'_, E> {
+        Chars(self.bytes.iter(), PhantomData)
+    }
+
+    /// Get an iterator over the encoded bytes of the string.
+    #[inline]
+    pub fn bytes(&self) -> Iter<'_, u8> {
+        self.bytes.iter()
+    }
+}
+
+impl<E: Encoding> fmt::Debug for PascalStr<E> {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.write_char('"')?;
+        for ch in self.chars() {
+            for escaped in ch.escape_debug() {
+                fmtr.write_char(escaped)?;
+            }
+        }
+        fmtr.write_char('"')
+    }
+}
+
+impl<E: Encoding> fmt::Display for PascalStr<E> {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.chars() {
+            fmtr.write_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> PartialEq for PascalStr<E> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<E> Eq for PascalStr<E> { }
+
+impl<E: Encoding> PartialEq<str> for PascalStr<E> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl<'a, E: Encoding> PartialEq<&'a str> for PascalStr<E> {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl<E> PartialOrd for PascalStr<E> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for PascalStr<E> {
+    /// Compares the encoded bytes of the strings.
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl<E> Hash for PascalStr<E> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+    }
+}
+
+impl<E: Encoding> Default for &PascalStr<E> {
+    #[inline]
+    fn default() -> Self {
+        PascalStr::from_bytes(&[])
+    }
+}
+
+impl<E> AsRef<[u8]> for PascalStr<E> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<E> Index<u8> for PascalStr<E> {
+    type Output = u8;
+
+    #[inline]
+    fn index(&self, index: u8) -> &Self::Output {
+        &self.bytes[index as usize]
+    }
+}
+
+impl<E> IndexMut<u8> for PascalStr<E> {
+    #[inline]
+    fn index_mut(&mut self, index: u8) -> &mut Self::Output {
+        &mut self.bytes[index as usize]
+    }
+}
+
+impl<E> Index<usize> for PascalStr<E> {
+    type Output = u8;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.bytes[index]
+    }
+}
+
+impl<E> IndexMut<usize> for PascalStr<E> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.bytes[index]
+    }
+}
+
+impl<E: Encoding> Index<RangeFull> for PascalStr<E> {
+    type Output = PascalStr<E>;
+
+    #[inline]
+    fn index(&self, _: RangeFull) -> &Self::Output {
+        self
+    }
+}
+
+impl<E: Encoding> Index<Range<usize>> for PascalStr<E> {
+    type Output = PascalStr<E>;
+
+    #[inline]
+    fn index(&self, index: Range<usize>) -> &Self::Output {
+        PascalStr::from_bytes(&self.bytes[index])
+    }
+}
+
+impl<E: Encoding> Index<RangeFrom<usize>> for PascalStr<E> {
+    type Output = PascalStr<E>;
+
+    #[inline]
+    fn index(&self, index: RangeFrom<usize>) -> &Self::Output {
+        PascalStr::from_bytes(&self.bytes[index])
+    }
+}
+
+impl<E: Encoding> Index<RangeTo<usize>> for PascalStr<E> {
+    type Output = PascalStr<E>;
+
+    #[inline]
+    fn index(&self, index: RangeTo<usize>) -> &Self::Output {
+        PascalStr::from_bytes(&self.bytes[index])
+    }
+}
+
+impl<'a, E: Encoding> IntoIterator for &'a PascalStr<E> {
+    type Item = char;
+    type IntoIter = Chars<'a, E>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.chars()
+    }
+}
+
+/// An iterator over the decoded characters of a `PascalStr`.
+#[derive(Clone, Debug)]
+pub struct Chars<'a, E>(Iter<'a, u8>, PhantomData<E>);
+
+impl<'a, E: Encoding> Iterator for Chars<'a, E> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|&b| E::decode(b))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, E: Encoding> DoubleEndedIterator for Chars<'a, E> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|&b| E::decode(b))
+    }
+}
+
+impl<'a, E: Encoding> ExactSizeIterator for Chars<'a, E> { }
@@ -0,0 +1,593 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::str::FromStr;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "alloc")]
+use ::ascii::InteriorNullError;
+use ::codepage::{Cp437, EncodeError, Encoding, Latin1, MacRoman, PascalStr, Windows1252};
+use ::prefix::{LengthPrefix, U8};
+use ::ConversionLoss;
+#[cfg(feature = "std")]
+use ::Layout;
+use ::PASCAL_STRING_BUF_SIZE;
+
+/// An owned `PascalString`. This string type stores its data the stack. With the default length prefix, it is
+/// always `N + 1` bytes long, with the first byte storing the length.
+///
+/// Each character is stored as a single byte in the encoding `E`, such as `Latin1` or `MacRoman`, so the
+/// capacity `N` is both the number of bytes and the number of characters the string can hold. It defaults to
+/// 255, and may not be larger than the maximum length which can be stored in the length prefix `L`.
+pub struct PascalString<E, const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
+    /// The length of this string.
+    len: L::Len,
+    /// The characters of this string, encoded in `E`.
+    chars_buf: [u8; N],
+    encoding: PhantomData<E>
+}
+
+/// A Latin-1 encoded `PascalString`.
+pub type Latin1String<const N: usize = PASCAL_STRING_BUF_SIZE, L = U8> = PascalString<Latin1, N, L>;
+
+/// A Windows-1252 encoded `PascalString`.
+pub type Windows1252String<const N: usize = PASCAL_STRING_BUF_SIZE, L = U8> = PascalString<Windows1252, N, L>;
+
+/// A Mac OS Roman encoded `PascalString`.
+pub type MacRomanString<const N: usize = PASCAL_STRING_BUF_SIZE, L = U8> = PascalString<MacRoman, N, L>;
+
+/// A code page 437 encoded `PascalString`.
+pub type Cp437String<const N: usize = PASCAL_STRING_BUF_SIZE, L = U8> = PascalString<Cp437, N, L>;
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> PascalString<E, N, L> {
+    /// Creates a new, empty `PascalString`.
+    #[inline]
+    pub const fn new() -> Self {
+        let () = ::CapacityCheck::<L, N>::VALID;
+        PascalString {
+            len: L::EMPTY,
+            chars_buf: [0u8; N],
+            encoding: PhantomData
+        }
+    }
+
+    /// Create a new `PascalString` by encoding the characters of `s`.
+    ///
+    /// Returns an `Err` if `s` has more characters than the capacity of the string, or if one of its
+    /// characters cannot be represented in the encoding `E`.
+    #[inline]
+    pub fn from_str<S: AsRef<str>>(s: S) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_str(s.as_ref())
+    }
+
+    fn _from_str(s: &str) -> Result<Self, PascalStringCreateError> {
+        let mut string = PascalString::new();
+        match string._try_push_str(s) {
+            Ok(()) => Ok(string),
+            Err(PascalStringAppendError::NoRoom { required, available }) => {
+                Err(PascalStringCreateError::InputTooLong { required: required, available: available })
+            }
+            Err(PascalStringAppendError::Unencodable(e)) => Err(PascalStringCreateError::Unencodable(e))
+        }
+    }
+
+    /// Create a new `PascalString` from bytes which are already encoded in `E`.
+    ///
+    /// Returns an `Err` if `bytes` is longer than the capacity of the string. Any byte is a valid character,
+    /// so the contents of `bytes` are not checked.
+    #[inline]
+    pub fn from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_bytes(bytes.as_ref())
+    }
+
+    fn _from_bytes(bytes: &[u8]) -> Result<Self, PascalStringCreateError> {
+        let len = bytes.len();
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
+        }
+        let mut string = PascalString::new();
+        string.chars_buf[..len].copy_from_slice(bytes);
+        string.set_len(len);
+        Ok(string)
+    }
+
+    /// Create a new `PascalString` from `s`, replacing any characters which cannot be encoded in `E` with
+    /// `replacement` (typically `b'?'`), and cutting off any characters which do not fit into the string.
+    ///
+    /// The returned `ConversionLoss` reports how many characters were replaced, and how many bytes of `s` were
+    /// cut off.
+    pub fn from_lossy<S: AsRef<str>>(s: S, replacement: u8) -> (Self, ConversionLoss) {
+        let s = s.as_ref();
+        let mut string = PascalString::new();
+        let mut loss = ConversionLoss::default();
+        for (i, ch) in s.char_indices() {
+            if string.is_full() {
+                loss.truncated = s.len() - i;
+                break;
+            }
+            let byte = E::encode(ch).unwrap_or_else(|| {
+                loss.replaced += 1;
+                replacement
+            });
+            let len = string.len();
+            string.chars_buf[len] = byte;
+            string.set_len(len + 1);
+        }
+        string.set_trailing_byte_to_null();
+        (string, loss)
+    }
+
+    /// Push a character onto the end of the string's internal buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no room to store the `char`, or it cannot be encoded in `E`.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.try_push(ch).unwrap()
+    }
+
+    /// Attempt to push a `char` onto the end of this string's internal buffer.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the operation succeeded, otherwise an error is returned.
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        if len == N {
+            return Err(PascalStringAppendError::NoRoom { required: 1, available: 0 });
+        }
+        let byte = E::encode(ch).ok_or_else(|| EncodeError::new::<E>(ch, 0))?;
+        self.chars_buf[len] = byte;
+        self.set_len(len + 1);
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Push a string onto the end of this string's internal buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no room to store the `str`, or one of its characters cannot be encoded in `E`.
+    #[inline]
+    pub fn push_str<S: AsRef<str>>(&mut self, s: S) {
+        self.try_push_str(s).unwrap()
+    }
+
+    /// Attempt to push a string onto the end of this string's internal buffer.
+    ///
+    /// If a character of `s` cannot be encoded, the error reports the character and its byte index in `s`.
+    /// The string is left unchanged if an error is returned.
+    #[inline]
+    pub fn try_push_str<S: AsRef<str>>(&mut self, s: S) -> Result<(), PascalStringAppendError> {
+        self._try_push_str(s.as_ref())
+    }
+
+    fn _try_push_str(&mut self, s: &str) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        let count = s.chars().count();
+        if len + count > N {
+            return Err(PascalStringAppendError::NoRoom { required: count, available: N - len });
+        }
+        for (i, (index, ch)) in s.char_indices().enumerate() {
+            match E::encode(ch) {
+                Some(byte) => self.chars_buf[len + i] = byte,
+                None => {
+                    self.set_trailing_byte_to_null();
+                    return Err(PascalStringAppendError::Unencodable(EncodeError::new::<E>(ch, index)));
+                }
+            }
+        }
+        self.set_len(len + count);
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Removes the last character from the string buffer and returns it.
+    ///
+    /// Returns `None` if this `PascalString` is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        let ch = E::decode(self.chars_buf[len - 1]);
+        self.set_len(len - 1);
+        self.set_trailing_byte_to_null();
+        Some(ch)
+    }
+
+    /// Truncates this String, removing all contents.
+    ///
+    /// Does not zero the values of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set_len(0);
+        self.set_trailing_byte_to_null();
+    }
+
+    /// Shortens this string to `new_len` characters. Has no effect if `new_len` is not less than the length
+    /// of the string.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            self.set_len(new_len);
+            self.set_trailing_byte_to_null();
+        }
+    }
+
+    /// Create a new `PascalString` from `reader`, by reading a length prefix followed by the string data, laid
+    /// out as described by `layout`.
+    ///
+    /// If the length prefix is larger than the capacity of the string, then an error of kind
+    /// `io::ErrorKind::InvalidData` is returned, which wraps a `PascalStringCreateError`.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R, layout: Layout) -> io::Result<Self> {
+        let len = ::read_prefix::<L, R>(reader)?;
+        if len > N {
+            let error = PascalStringCreateError::InputTooLong { required: len, available: N };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        let mut string = PascalString::new();
+        reader.read_exact(&mut string.chars_buf[..len])?;
+        string.set_len(len);
+        string.set_trailing_byte_to_null();
+        if layout == Layout::Record {
+            ::skip_bytes(reader, N - len)?;
+        }
+        Ok(string)
+    }
+
+    /// Write this `PascalString` to `writer`, as a length prefix followed by the string data, laid out
+    /// as described by `layout`.
+    ///
+    /// Unlike `to_array()`, stale bytes past the end of the string are never written.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_to<W: Write>(&self, writer: &mut W, layout: Layout) -> io::Result<()> {
+        ::write_prefixed::<L, W>(writer, self.as_bytes(), N, layout)
+    }
+
+    /// Returns the maximum number of characters which this `PascalString` can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the length of the string is equal to its capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Get this string as a `CStr`.
+    ///
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If this string is not
+    /// full, then the trailing null byte which is kept past the end of the string is used, so no allocation
+    /// is made. If the string is full, then a new `CString` will be allocated to hold the trailing null byte.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        if self.is_full() {
+            return self.deref().as_cstr();
+        }
+        let len = self.len();
+        let bytes = &self.chars_buf[..len + 1];
+        match bytes.iter().position(|&b| b == 0) {
+            Some(pos) if pos < len && pos != len - 1 => Err(InteriorNullError::new(pos)),
+            Some(pos) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(&bytes[..pos + 1]).unwrap())),
+            None => unreachable!("the byte after the end of a PascalString must be null")
+        }
+    }
+
+    /// Consumes this `PascalString`, and returns its inner state as a `[u8; M]`, where the first bytes
+    /// store the length prefix, and the rest store the encoded contents of the string. `M` must be equal
+    /// to the capacity of the string plus the width of the length prefix.
+    ///
+    /// Using an `M` which is not equal to `N + L::WIDTH` is a compile time error.
+    #[inline]
+    pub fn to_array<const M: usize>(self) -> [u8; M] {
+        let () = ::ArrayLenCheck::<L, N, M>::VALID;
+        let mut array = [0u8; M];
+        L::write_len(self.len, &mut array);
+        array[L::WIDTH..].copy_from_slice(&self.chars_buf);
+        array
+    }
+
+    /// Sets the byte beyond the end of `len` to `0`, if this `PascalString` isn't full.
+    ///
+    /// Used to ensure that `PascalString::as_cstr()` works correctly.
+    #[inline]
+    fn set_trailing_byte_to_null(&mut self) {
+        if !self.is_full() {
+            self.chars_buf[self.len()] = 0;
+        }
+    }
+
+    /// Sets the length of the string, without touching the contents of the buffer.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+        self.len = L::from_usize(len);
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> fmt::Debug for PascalString<E, N, L> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("PascalString")
+            .field("len", &self.len)
+            .field("chars_buf", &self.deref())
+            .finish()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> fmt::Display for PascalString<E, N, L> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.deref(), fmtr)
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> fmt::Write for PascalString<E, N, L> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Default for PascalString<E, N, L> {
+    #[inline]
+    fn default() -> Self {
+        PascalString::new()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Clone for PascalString<E, N, L> {
+    #[inline]
+    fn clone(&self) -> Self {
+        PascalString {
+            len: self.len,
+            chars_buf: self.chars_buf,
+            encoding: PhantomData
+        }
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Hash for PascalString<E, N, L> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state);
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> PartialEq for PascalString<E, N, L> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Eq for PascalString<E, N, L> { }
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> PartialEq<str> for PascalString<E, N, L> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.deref() == other
+    }
+}
+
+impl<'a, E: Encoding, const N: usize, L: LengthPrefix> PartialEq<&'a str> for PascalString<E, N, L> {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.deref() == *other
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> PartialOrd for PascalString<E, N, L> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Ord for PascalString<E, N, L> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Deref for PascalString<E, N, L> {
+    type Target = PascalStr<E>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        PascalStr::from_bytes(&self.chars_buf[..L::to_usize(self.len)])
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> DerefMut for PascalString<E, N, L> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        PascalStr::from_bytes_mut(&mut self.chars_buf[..L::to_usize(self.len)])
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> AsRef<PascalStr<E>> for PascalString<E, N, L> {
+    #[inline]
+    fn as_ref(&self) -> &PascalStr<E> {
+        self.deref()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> AsRef<[u8]> for PascalString<E, N, L> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> Borrow<PascalStr<E>> for PascalString<E, N, L> {
+    #[inline]
+    fn borrow(&self) -> &PascalStr<E> {
+        self.deref()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> BorrowMut<PascalStr<E>> for PascalString<E, N, L> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut PascalStr<E> {
+        self.deref_mut()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Encoding, const N: usize, L: LengthPrefix> From<PascalString<E, N, L>> for String {
+    #[inline]
+    fn from(string: PascalString<E, N, L>) -> Self {
+        string.chars().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Encoding, const N: usize, L: LengthPrefix> From<PascalString<E, N, L>> for Vec<u8> {
+    #[inline]
+    fn from(string: PascalString<E, N, L>) -> Self {
+        string.as_bytes().to_vec()
+    }
+}
+
+impl<E: Encoding, const N: usize, L: LengthPrefix> FromStr for PascalString<E, N, L> {
+    type Err = PascalStringCreateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PascalString::_from_str(s)
+    }
+}
+
+/// Indicates the range of errors which can occur from creating a new `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringCreateError {
+    /// The data provided to the constructor was larger than the `PascalString` could store.
+    InputTooLong {
+        /// The number of characters in the data.
+        required: usize,
+        /// The capacity of the string.
+        available: usize
+    },
+    /// A character of the data cannot be represented in the encoding of the string.
+    Unencodable(EncodeError)
+}
+
+impl PascalStringCreateError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringCreateError::InputTooLong { .. } => "the input data is longer than what a PascalString can store",
+            PascalStringCreateError::Unencodable(_) => "the input data cannot be encoded"
+        }
+    }
+}
+
+impl fmt::Display for PascalStringCreateError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringCreateError::InputTooLong { required, available } => {
+                write!(fmtr, "{} ({} characters required, {} available)", self.message(), required, available)
+            }
+            PascalStringCreateError::Unencodable(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringCreateError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PascalStringCreateError::Unencodable(ref e) = *self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<EncodeError> for PascalStringCreateError {
+    #[inline]
+    fn from(e: EncodeError) -> Self {
+        PascalStringCreateError::Unencodable(e)
+    }
+}
+
+/// Indicates the range of errors which can occur from appending string data to a `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringAppendError {
+    /// There is no room to store the appended data.
+    NoRoom {
+        /// The number of characters in the appended data.
+        required: usize,
+        /// The number of characters which were left in the string.
+        available: usize
+    },
+    /// A character of the appended data cannot be represented in the encoding of the string.
+    Unencodable(EncodeError)
+}
+
+impl PascalStringAppendError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringAppendError::NoRoom { .. } => "there is no room for the string to be appended",
+            PascalStringAppendError::Unencodable(_) => "the appended data cannot be encoded"
+        }
+    }
+}
+
+impl fmt::Display for PascalStringAppendError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringAppendError::NoRoom { required, available } => {
+                write!(fmtr, "{} ({} characters required, {} available)", self.message(), required, available)
+            }
+            PascalStringAppendError::Unencodable(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringAppendError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PascalStringAppendError::Unencodable(ref e) = *self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<EncodeError> for PascalStringAppendError {
+    #[inline]
+    fn from(e: EncodeError) -> Self {
+        PascalStringAppendError::Unencodable(e)
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(feature = "std")]
+mod record;
+mod short_string;
+
+#[cfg(feature = "std")]
+pub use self::record::{read_records, write_records, Record};
+pub use self::short_string::{ShortString, StaleBytes};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+    use ::codepage::{PascalStringCreateError, Windows1252, Windows1252String};
+    #[cfg(feature = "std")]
+    use std::io::{self, Read, Write};
+
+    // A `string[7]` which held "Delphi!" before "Pas" was assigned to it.
+    const STALE_RECORD: [u8; 8] = *b"\x03Pashi!\xe9";
+
+    #[test]
+    fn test_stale_bytes() {
+        let kept = ShortString::<Windows1252, 7>::from_array(STALE_RECORD, StaleBytes::Keep).unwrap();
+        assert_eq!(kept, "Pas");
+        assert_eq!(kept.stale_bytes(), b"hi!\xe9");
+        assert_eq!(kept.to_array(), STALE_RECORD);
+
+        let zeroed = ShortString::<Windows1252, 7>::from_array(STALE_RECORD, StaleBytes::Zero).unwrap();
+        assert_eq!(zeroed, kept);
+        assert_eq!(zeroed.to_array(), *b"\x03Pas\0\0\0\0");
+
+        let mut bad = STALE_RECORD;
+        bad[0] = 8;
+        assert_eq!(ShortString::<Windows1252, 7>::from_array(bad, StaleBytes::Keep),
+                   Err(PascalStringCreateError::InputTooLong { required: 8, available: 7 }));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let string = ShortString::<Windows1252, 7>::from_str("Café").unwrap();
+        assert_eq!(string.as_bytes(), b"Caf\xe9");
+        assert_eq!(string.to_string(), "Café");
+        assert_eq!(ShortString::<Windows1252>::SIZE, 256);
+
+        let editable = Windows1252String::<7>::from(string);
+        assert_eq!(editable, "Café");
+        assert_eq!(ShortString::from(editable), string);
+        assert!(ShortString::<Windows1252, 3>::from_str("Café").is_err());
+        assert_eq!("Café".parse::<ShortString<Windows1252, 7>>(), Ok(string));
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Debug, PartialEq)]
+    struct Entry {
+        name: ShortString<Windows1252, 7>,
+        id: u16
+    }
+
+    #[cfg(feature = "std")]
+    impl Record for Entry {
+        const SIZE: usize = 8 + 2;
+
+        fn read_record<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self> {
+            Ok(Entry {
+                name: Record::read_record(reader, stale)?,
+                id: Record::read_record(reader, stale)?
+            })
+        }
+
+        fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.name.write_record(writer)?;
+            self.id.write_record(writer)
+        }
+    }
+
+    // An `Entry` whose `SIZE` wrongly counts two bytes of padding.
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    struct PaddedEntry(Entry);
+
+    #[cfg(feature = "std")]
+    impl Record for PaddedEntry {
+        const SIZE: usize = Entry::SIZE + 2;
+
+        fn read_record<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self> {
+            Entry::read_record(reader, stale).map(PaddedEntry)
+        }
+
+        fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.0.write_record(writer)?;
+            writer.write_all(&[0, 0])
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_of_record() {
+        let mut file = Vec::new();
+        file.extend_from_slice(&STALE_RECORD);
+        file.extend_from_slice(&[0x34, 0x12]);
+        file.extend_from_slice(b"\x06Turbo!\0\x01\0");
+
+        let entries: Vec<Entry> = read_records(&mut &file[..], StaleBytes::Keep).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "Pas");
+        assert_eq!(entries[0].id, 0x1234);
+        assert_eq!(entries[1].name, "Turbo!");
+        assert_eq!(entries[1].id, 1);
+
+        let mut written = Vec::new();
+        write_records(&mut written, &entries).unwrap();
+        assert_eq!(written, file);
+
+        let entries: Vec<Entry> = read_records(&mut &file[..], StaleBytes::Zero).unwrap();
+        assert_eq!(entries[0].name.stale_bytes(), b"\0\0\0\0");
+
+        let err = read_records::<Entry, _>(&mut &file[..15], StaleBytes::Keep).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let err = read_records::<PaddedEntry, _>(&mut &file[..12], StaleBytes::Keep).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        file[0] = 9;
+        let err = read_records::<Entry, _>(&mut &file[..], StaleBytes::Keep).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
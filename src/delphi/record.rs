@@ -0,0 +1,130 @@
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+use ::codepage::Encoding;
+use ::delphi::{ShortString, StaleBytes};
+
+/// A type which is stored as a fixed size record, such as the element type of a Delphi `file of record`.
+///
+/// Implement this trait for a `packed record` by reading and writing each of its fields in order. Integers and
+/// floats are stored in little endian byte order, as they are by Delphi on x86.
+///
+/// ```
+/// use pascal_string::codepage::Windows1252;
+/// use pascal_string::delphi::{read_records, Record, ShortString, StaleBytes};
+/// use std::io::{self, Read, Write};
+///
+/// // type TCustomer = packed record Name: string[15]; Age: Integer; end;
+/// struct Customer {
+///     name: ShortString<Windows1252, 15>,
+///     age: i32
+/// }
+///
+/// impl Record for Customer {
+///     const SIZE: usize = 16 + 4;
+///
+///     fn read_record<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self> {
+///         Ok(Customer {
+///             name: Record::read_record(reader, stale)?,
+///             age: Record::read_record(reader, stale)?
+///         })
+///     }
+///
+///     fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+///         self.name.write_record(writer)?;
+///         self.age.write_record(writer)
+///     }
+/// }
+///
+/// let file = b"\x05Ren\xe9e\0\0\0\0\0\0\0\0\0\0\x2a\0\0\0";
+/// let customers: Vec<Customer> = read_records(&mut &file[..], StaleBytes::Zero).unwrap();
+/// assert_eq!(customers[0].name, "Renée");
+/// assert_eq!(customers[0].age, 42);
+/// ```
+pub trait Record: Sized {
+    /// The size of the record in bytes.
+    const SIZE: usize;
+
+    /// Read the record from `reader`. `stale` is passed on to the `ShortString` fields of the record.
+    fn read_record<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self>;
+
+    /// Write the record to `writer`. Exactly `SIZE` bytes must be written.
+    fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+impl<E: Encoding, const N: usize> Record for ShortString<E, N> {
+    const SIZE: usize = N + 1;
+
+    #[inline]
+    fn read_record<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self> {
+        ShortString::read_from(reader, stale)
+    }
+
+    #[inline]
+    fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+macro_rules! impl_record_for_number {
+    ($($ty:ty),*) => {
+        $(
+            impl Record for $ty {
+                const SIZE: usize = ::core::mem::size_of::<$ty>();
+
+                #[inline]
+                fn read_record<R: Read>(reader: &mut R, _: StaleBytes) -> io::Result<Self> {
+                    let mut bytes = [0u8; ::core::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+
+                #[inline]
+                fn write_record<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+        )*
+    }
+}
+
+impl_record_for_number!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Read every record from `reader` until the end of the stream, as Delphi does when it reads a `file of record`.
+///
+/// If the stream ends part way through a record, then an error of kind `io::ErrorKind::UnexpectedEof` is
+/// returned. If `T::read_record()` does not read all of the `T::SIZE` bytes of a record, then an error of kind
+/// `io::ErrorKind::InvalidData` is returned, as `T::SIZE` does not match the fields of the record.
+pub fn read_records<T: Record, R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Vec<T>> {
+    let mut records = Vec::new();
+    let mut buf = vec![0u8; T::SIZE];
+    loop {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => { }
+                Err(e) => return Err(e)
+            }
+        }
+        if filled == 0 {
+            return Ok(records);
+        }
+        if filled < buf.len() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the stream ended part way through a record"));
+        }
+        let mut record = &buf[..];
+        records.push(T::read_record(&mut record, stale)?);
+        if !record.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "the record did not read all of its SIZE bytes"));
+        }
+    }
+}
+
+/// Write each of `records` to `writer`, producing a `file of record` which Delphi can read.
+pub fn write_records<T: Record, W: Write>(writer: &mut W, records: &[T]) -> io::Result<()> {
+    for record in records {
+        record.write_record(writer)?;
+    }
+    Ok(())
+}
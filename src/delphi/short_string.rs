@@ -0,0 +1,264 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+use ::codepage::{self, Encoding, PascalStringCreateError};
+use ::prefix::U8;
+use ::PASCAL_STRING_BUF_SIZE;
+
+/// A Delphi or Turbo Pascal `string[N]`, laid out exactly as it is in a record: a single length byte followed by
+/// `N` bytes of characters in the encoding `E`. `ShortString<E>` is the Delphi `ShortString` type, which is a
+/// `string[255]`.
+///
+/// Delphi does not clear the bytes past the end of a string when a shorter string is assigned to it, so these
+/// stale bytes show up in data files. A `ShortString` stores them, and whether they are kept or zeroed when a
+/// string is read is chosen with `StaleBytes`. Writing a `ShortString` writes back exactly the bytes it holds.
+///
+/// `ShortString` dereferences to a `codepage::PascalStr<E>`, which decodes the characters. The classic Mac OS
+/// Toolbox strings in the `mac` module are `ShortString`s in Mac OS Roman.
+#[repr(C)]
+pub struct ShortString<E, const N: usize = PASCAL_STRING_BUF_SIZE> {
+    /// The length of this string.
+    len: u8,
+    /// The characters of this string, encoded in `E`, followed by any stale bytes.
+    chars_buf: [u8; N],
+    encoding: PhantomData<E>
+}
+
+/// Describes what happens to the bytes past the end of a `ShortString` when it is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StaleBytes {
+    /// The bytes are set to zero.
+    Zero,
+    /// The bytes are kept as they are, so the string is written back bit for bit.
+    Keep
+}
+
+impl<E: Encoding, const N: usize> ShortString<E, N> {
+    /// The size of the record field, which is `N + 1` bytes.
+    pub const SIZE: usize = N + 1;
+
+    /// Creates a new, empty `ShortString`.
+    #[inline]
+    pub const fn new() -> Self {
+        let () = ::CapacityCheck::<U8, N>::VALID;
+        ShortString {
+            len: 0,
+            chars_buf: [0u8; N],
+            encoding: PhantomData
+        }
+    }
+
+    /// Create a new `ShortString` by encoding the characters of `s` in `E`.
+    ///
+    /// Returns an `Err` if `s` has more than `N` characters, or if one of its characters cannot be represented
+    /// in `E`.
+    #[inline]
+    pub fn from_str<S: AsRef<str>>(s: S) -> Result<Self, PascalStringCreateError> {
+        codepage::PascalString::<E, N>::from_str(s).map(ShortString::from)
+    }
+
+    /// Create a new `ShortString` from the bytes of a record field. The first byte is the length. `M` must be
+    /// equal to `N + 1`.
+    ///
+    /// Returns an `Err` if the length byte is larger than `N`.
+    ///
+    /// Using an `M` which is not equal to `N + 1` is a compile time error.
+    #[inline]
+    pub fn from_array<const M: usize>(array: [u8; M], stale: StaleBytes) -> Result<Self, PascalStringCreateError> {
+        let () = ::ArrayLenCheck::<U8, N, M>::VALID;
+        let len = array[0] as usize;
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
+        }
+        let mut string = ShortString::new();
+        string.len = array[0];
+        string.chars_buf.copy_from_slice(&array[1..]);
+        if stale == StaleBytes::Zero {
+            string.zero_stale_bytes();
+        }
+        Ok(string)
+    }
+
+    /// Consumes this `ShortString`, and returns the bytes of the record field as a `[u8; M]`, including any
+    /// stale bytes. `M` must be equal to `N + 1`.
+    ///
+    /// Using an `M` which is not equal to `N + 1` is a compile time error.
+    #[inline]
+    pub fn to_array<const M: usize>(self) -> [u8; M] {
+        let () = ::ArrayLenCheck::<U8, N, M>::VALID;
+        let mut array = [0u8; M];
+        array[0] = self.len;
+        array[1..].copy_from_slice(&self.chars_buf);
+        array
+    }
+
+    /// Read a record field of `N + 1` bytes from `reader`.
+    ///
+    /// If the length byte is larger than `N`, then an error of kind `io::ErrorKind::InvalidData` is returned,
+    /// which wraps a `PascalStringCreateError`.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R, stale: StaleBytes) -> io::Result<Self> {
+        let mut string = ShortString::new();
+        let mut len = [0u8; 1];
+        reader.read_exact(&mut len)?;
+        reader.read_exact(&mut string.chars_buf)?;
+        if len[0] as usize > N {
+            let error = PascalStringCreateError::InputTooLong { required: len[0] as usize, available: N };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        string.len = len[0];
+        if stale == StaleBytes::Zero {
+            string.zero_stale_bytes();
+        }
+        Ok(string)
+    }
+
+    /// Write the record field of `N + 1` bytes to `writer`, including any stale bytes.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[self.len])?;
+        writer.write_all(&self.chars_buf)
+    }
+
+    /// Returns the bytes past the end of the string.
+    #[inline]
+    pub fn stale_bytes(&self) -> &[u8] {
+        &self.chars_buf[self.len as usize..]
+    }
+
+    /// Sets the bytes past the end of the string to zero.
+    #[inline]
+    pub fn zero_stale_bytes(&mut self) {
+        for byte in &mut self.chars_buf[self.len as usize..] {
+            *byte = 0;
+        }
+    }
+
+    /// Returns the maximum number of characters which this `ShortString` can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<E: Encoding, const N: usize> fmt::Debug for ShortString<E, N> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("ShortString")
+            .field("len", &self.len)
+            .field("chars_buf", &self.deref())
+            .finish()
+    }
+}
+
+impl<E: Encoding, const N: usize> fmt::Display for ShortString<E, N> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.deref(), fmtr)
+    }
+}
+
+impl<E: Encoding, const N: usize> Default for ShortString<E, N> {
+    #[inline]
+    fn default() -> Self {
+        ShortString::new()
+    }
+}
+
+impl<E: Encoding, const N: usize> Clone for ShortString<E, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Encoding, const N: usize> Copy for ShortString<E, N> { }
+
+impl<E: Encoding, const N: usize> Hash for ShortString<E, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state);
+    }
+}
+
+impl<E: Encoding, const N: usize> PartialEq for ShortString<E, N> {
+    /// Compares the contents of the strings. Stale bytes are ignored.
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+
+impl<E: Encoding, const N: usize> Eq for ShortString<E, N> { }
+
+impl<E: Encoding, const N: usize> PartialEq<str> for ShortString<E, N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.deref() == other
+    }
+}
+
+impl<'a, E: Encoding, const N: usize> PartialEq<&'a str> for ShortString<E, N> {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.deref() == *other
+    }
+}
+
+impl<E: Encoding, const N: usize> Deref for ShortString<E, N> {
+    type Target = codepage::PascalStr<E>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        codepage::PascalStr::from_bytes(&self.chars_buf[..self.len as usize])
+    }
+}
+
+impl<E: Encoding, const N: usize> AsRef<[u8]> for ShortString<E, N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Encoding, const N: usize> From<ShortString<E, N>> for String {
+    /// Decodes the string from `E`.
+    #[inline]
+    fn from(string: ShortString<E, N>) -> Self {
+        string.chars().collect()
+    }
+}
+
+impl<E: Encoding, const N: usize> FromStr for ShortString<E, N> {
+    type Err = PascalStringCreateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        codepage::PascalString::<E, N>::from_str(s).map(ShortString::from)
+    }
+}
+
+impl<E: Encoding, const N: usize> From<codepage::PascalString<E, N>> for ShortString<E, N> {
+    /// Converts a `codepage::PascalString` into a `ShortString` with no stale bytes.
+    #[inline]
+    fn from(string: codepage::PascalString<E, N>) -> Self {
+        let mut short_string = ShortString::new();
+        short_string.len = string.len() as u8;
+        short_string.chars_buf[..string.len()].copy_from_slice(string.as_bytes());
+        short_string
+    }
+}
+
+impl<E: Encoding, const N: usize> From<ShortString<E, N>> for codepage::PascalString<E, N> {
+    /// Converts a `ShortString` into a `codepage::PascalString`, which can be edited. Stale bytes are dropped.
+    #[inline]
+    fn from(string: ShortString<E, N>) -> Self {
+        codepage::PascalString::from_bytes(string.as_bytes()).unwrap()
+    }
+}
@@ -0,0 +1,405 @@
+use ascii_crate::AsciiStr;
+use core::fmt::{self, Write};
+use core::hash::{Hash, Hasher};
+use core::iter::Iterator;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error::Error;
+use ::ascii::PascalStr;
+
+/// A domain name, stored in the uncompressed DNS wire format.
+///
+/// On the wire, a domain name is a sequence of labels, each of which is an ascii pascal string with a single
+/// byte length prefix, terminated by the empty root label. Each label holds between 1 and 63 characters, and the
+/// whole name, including the length prefixes and the root label, may be at most 255 bytes long.
+///
+/// Labels may only use letters, digits, hyphens and underscores, and may not start or end with a hyphen. This is
+/// the letter, digit and hyphen rule for host names, extended with the underscores which service names such as
+/// `_sip._tcp.example.com` use. Labels never contain dots, so the text form of a name always parses back to it.
+///
+/// Domain names compare and hash without regard to ascii case, as DNS does.
+#[derive(Clone, Copy)]
+pub struct DomainName {
+    /// The length of the wire format, including the root label.
+    len: u8,
+    /// The wire format of the name.
+    bytes: [u8; DomainName::MAX_LEN]
+}
+
+impl DomainName {
+    /// The maximum length of a single label.
+    pub const MAX_LABEL_LEN: usize = 63;
+
+    /// The maximum length of a domain name in the wire format, including the length prefixes and the root label.
+    pub const MAX_LEN: usize = 255;
+
+    /// Creates the root domain name, which has no labels.
+    #[inline]
+    pub fn root() -> Self {
+        DomainName {
+            len: 1,
+            bytes: [0; DomainName::MAX_LEN]
+        }
+    }
+
+    /// Parse a domain name in the dotted text form, such as `"www.example.com"`. A trailing dot is allowed, and
+    /// `"."` on its own is the root domain name.
+    ///
+    /// Returns an `Err` if a label is empty, too long or contains an invalid character, or if the name is too
+    /// long. Offsets in the error are byte indices into `s`.
+    #[inline]
+    pub fn from_str<S: AsRef<str>>(s: S) -> Result<Self, DomainNameError> {
+        DomainName::_from_str(s.as_ref())
+    }
+
+    fn _from_str(s: &str) -> Result<Self, DomainNameError> {
+        let mut name = DomainName::root();
+        if s == "." {
+            return Ok(name);
+        }
+        let labels = s.strip_suffix('.').unwrap_or(s);
+        let mut offset = 0;
+        for label in labels.split('.') {
+            if label.is_empty() {
+                return Err(DomainNameError::EmptyLabel { offset: offset });
+            }
+            if label.len() > DomainName::MAX_LABEL_LEN {
+                return Err(DomainNameError::LabelTooLong {
+                    required: label.len(),
+                    available: DomainName::MAX_LABEL_LEN
+                });
+            }
+            validate_label(label.as_bytes()).map_err(|i| DomainNameError::InvalidCharacter { offset: offset + i })?;
+            name.append_label(label.as_bytes())?;
+            offset += label.len() + 1;
+        }
+        Ok(name)
+    }
+
+    /// Parse an uncompressed domain name in the wire format from the start of `bytes`.
+    ///
+    /// On success, the `DomainName` is returned along with the remainder of `bytes` which follows the end of the
+    /// name.
+    ///
+    /// Returns an `Err` if the name is truncated, malformed, contains an invalid character, or contains a message
+    /// compression pointer. Use `DomainName::from_packet()` to parse names which may be compressed.
+    pub fn from_wire(bytes: &[u8]) -> Result<(Self, &[u8]), DomainNameError> {
+        let (name, end) = DomainName::parse(bytes, 0, false)?;
+        Ok((name, &bytes[end..]))
+    }
+
+    /// Parse a domain name in the wire format starting at `offset` in the DNS message `packet`, following any
+    /// message compression pointers.
+    ///
+    /// On success, the `DomainName` is returned along with the offset in `packet` which follows the end of the
+    /// name. If the name is compressed, this is the offset after the first pointer.
+    ///
+    /// Returns an `Err` if the name is truncated, malformed or contains an invalid character, or if a pointer
+    /// does not point to an earlier offset in `packet` than the labels it follows. This rules out pointer loops.
+    /// Offsets in the error are byte offsets into `packet`.
+    #[inline]
+    pub fn from_packet(packet: &[u8], offset: usize) -> Result<(Self, usize), DomainNameError> {
+        DomainName::parse(packet, offset, true)
+    }
+
+    fn parse(packet: &[u8], offset: usize, follow_pointers: bool) -> Result<(Self, usize), DomainNameError> {
+        let mut name = DomainName { len: 0, bytes: [0; DomainName::MAX_LEN] };
+        let mut pos = offset;
+        let mut run_start = offset;
+        let mut end = None;
+        loop {
+            let prefix = match packet.get(pos) {
+                Some(&prefix) => prefix,
+                None => return Err(DomainNameError::Truncated { required: pos + 1, available: packet.len() })
+            };
+            match prefix & 0xC0 {
+                0x00 if prefix == 0 => {
+                    name.bytes[name.len as usize] = 0;
+                    name.len += 1;
+                    return Ok((name, end.unwrap_or(pos + 1)));
+                }
+                0x00 => {
+                    let start = pos + 1;
+                    let label_end = start + prefix as usize;
+                    if packet.len() < label_end {
+                        return Err(DomainNameError::Truncated { required: label_end, available: packet.len() });
+                    }
+                    let label = &packet[start..label_end];
+                    validate_label(label).map_err(|i| DomainNameError::InvalidCharacter { offset: start + i })?;
+                    // Leave room for the root label, which is written when the end of the name is reached.
+                    let required = name.len as usize + 1 + label.len() + 1;
+                    if required > DomainName::MAX_LEN {
+                        return Err(DomainNameError::NameTooLong {
+                            required: required,
+                            available: DomainName::MAX_LEN
+                        });
+                    }
+                    let len = name.len as usize;
+                    name.bytes[len] = prefix;
+                    name.bytes[len + 1..len + 1 + label.len()].copy_from_slice(label);
+                    name.len += 1 + prefix;
+                    pos = label_end;
+                }
+                0xC0 if follow_pointers => {
+                    let low = match packet.get(pos + 1) {
+                        Some(&low) => low,
+                        None => return Err(DomainNameError::Truncated { required: pos + 2, available: packet.len() })
+                    };
+                    let target = ((prefix as usize & 0x3F) << 8) | low as usize;
+                    if target >= run_start {
+                        return Err(DomainNameError::BadPointer { offset: pos });
+                    }
+                    if end.is_none() {
+                        end = Some(pos + 2);
+                    }
+                    pos = target;
+                    run_start = target;
+                }
+                0xC0 => return Err(DomainNameError::BadPointer { offset: pos }),
+                _ => return Err(DomainNameError::InvalidLabelType { offset: pos })
+            }
+        }
+    }
+
+    /// Append `label` to the end of the name, before the root label.
+    ///
+    /// Returns an `Err` if the label is empty, too long or contains an invalid character, or if the name would
+    /// become too long. Offsets in the error are indices into `label`.
+    pub fn push_label(&mut self, label: &PascalStr) -> Result<(), DomainNameError> {
+        let bytes: &[u8] = label.as_ref();
+        if bytes.is_empty() {
+            return Err(DomainNameError::EmptyLabel { offset: 0 });
+        }
+        if bytes.len() > DomainName::MAX_LABEL_LEN {
+            return Err(DomainNameError::LabelTooLong { required: bytes.len(), available: DomainName::MAX_LABEL_LEN });
+        }
+        validate_label(bytes).map_err(|i| DomainNameError::InvalidCharacter { offset: i })?;
+        self.append_label(bytes)
+    }
+
+    /// Append a label which has already been validated.
+    fn append_label(&mut self, label: &[u8]) -> Result<(), DomainNameError> {
+        let root = self.len as usize - 1;
+        let required = self.len as usize + 1 + label.len();
+        if required > DomainName::MAX_LEN {
+            return Err(DomainNameError::NameTooLong { required: required, available: DomainName::MAX_LEN });
+        }
+        self.bytes[root] = label.len() as u8;
+        self.bytes[root + 1..root + 1 + label.len()].copy_from_slice(label);
+        self.bytes[required - 1] = 0;
+        self.len = required as u8;
+        Ok(())
+    }
+
+    /// Get the uncompressed wire format of the name, including the root label.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    /// Returns the length of the wire format of the name, including the root label.
+    #[inline]
+    pub fn wire_len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns true if this is the root domain name, which has no labels.
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.len == 1
+    }
+
+    /// Get an iterator over the labels of the name, not including the root label.
+    #[inline]
+    pub fn labels(&self) -> Labels<'_> {
+        Labels(self.as_bytes())
+    }
+}
+
+/// Checks that `label` only contains letters, digits, hyphens and underscores, and does not start or end with a
+/// hyphen. Returns the index of the first invalid character.
+fn validate_label(label: &[u8]) -> Result<(), usize> {
+    for (i, &byte) in label.iter().enumerate() {
+        let hyphen_allowed = i != 0 && i != label.len() - 1;
+        if !(byte.is_ascii_alphanumeric() || byte == b'_' || (byte == b'-' && hyphen_allowed)) {
+            return Err(i);
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+fn label_eq_ignore_case(left: &PascalStr, right: &PascalStr) -> bool {
+    left.as_str().eq_ignore_ascii_case(right.as_str())
+}
+
+impl fmt::Debug for DomainName {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "DomainName(\"{}\")", self)
+    }
+}
+
+impl fmt::Display for DomainName {
+    /// Formats the name in the dotted text form, with a trailing dot.
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_root() {
+            return fmtr.write_char('.');
+        }
+        for label in self.labels() {
+            fmtr.write_str(label.as_str())?;
+            fmtr.write_char('.')?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for DomainName {
+    #[inline]
+    fn default() -> Self {
+        DomainName::root()
+    }
+}
+
+impl PartialEq for DomainName {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.labels().zip(other.labels()).all(|(l, r)| label_eq_ignore_case(l, r))
+    }
+}
+
+impl Eq for DomainName { }
+
+impl Hash for DomainName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.as_bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+    }
+}
+
+impl AsRef<[u8]> for DomainName {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl FromStr for DomainName {
+    type Err = DomainNameError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DomainName::_from_str(s)
+    }
+}
+
+impl<'a> IntoIterator for &'a DomainName {
+    type Item = &'a PascalStr;
+    type IntoIter = Labels<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.labels()
+    }
+}
+
+/// An iterator over the labels of a `DomainName`.
+#[derive(Clone, Debug)]
+pub struct Labels<'a>(&'a [u8]);
+
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a PascalStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0.first() {
+            None | Some(&0) => None,
+            Some(&len) => {
+                let (label, rest) = self.0[1..].split_at(len as usize);
+                self.0 = rest;
+                Some(From::from(unsafe { AsciiStr::from_ascii_unchecked(label) }))
+            }
+        }
+    }
+}
+
+/// Indicates the range of errors which can occur when creating or parsing a `DomainName`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainNameError {
+    /// A label in the text form of the name is empty.
+    EmptyLabel {
+        /// The byte offset of the empty label.
+        offset: usize
+    },
+    /// A label is longer than 63 characters.
+    LabelTooLong {
+        /// The length of the label.
+        required: usize,
+        /// The maximum length of a label.
+        available: usize
+    },
+    /// The wire format of the name is longer than 255 bytes.
+    NameTooLong {
+        /// The length of the wire format of the name, up to and including the label which did not fit.
+        required: usize,
+        /// The maximum length of a name.
+        available: usize
+    },
+    /// A label contains a character which is not a letter, digit, hyphen or underscore, or starts or ends with a
+    /// hyphen.
+    InvalidCharacter {
+        /// The byte offset of the character.
+        offset: usize
+    },
+    /// The input ends before the end of the name.
+    Truncated {
+        /// The number of bytes needed to read the next part of the name.
+        required: usize,
+        /// The length of the input.
+        available: usize
+    },
+    /// A label length byte uses one of the reserved label types.
+    InvalidLabelType {
+        /// The byte offset of the length byte.
+        offset: usize
+    },
+    /// A message compression pointer was found where it is not allowed, or does not point backwards.
+    BadPointer {
+        /// The byte offset of the pointer.
+        offset: usize
+    }
+}
+
+impl DomainNameError {
+    fn message(&self) -> &'static str {
+        match *self {
+            DomainNameError::EmptyLabel { .. } => "the domain name contains an empty label",
+            DomainNameError::LabelTooLong { .. } => "a label of the domain name is too long",
+            DomainNameError::NameTooLong { .. } => "the domain name is too long",
+            DomainNameError::InvalidCharacter { .. } => "a label of the domain name contains an invalid character",
+            DomainNameError::Truncated { .. } => "the input ends before the end of the domain name",
+            DomainNameError::InvalidLabelType { .. } => "the domain name uses a reserved label type",
+            DomainNameError::BadPointer { .. } => "the domain name contains an invalid compression pointer"
+        }
+    }
+}
+
+impl fmt::Display for DomainNameError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DomainNameError::LabelTooLong { required, available } |
+            DomainNameError::NameTooLong { required, available } |
+            DomainNameError::Truncated { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            DomainNameError::EmptyLabel { offset } |
+            DomainNameError::InvalidCharacter { offset } |
+            DomainNameError::InvalidLabelType { offset } |
+            DomainNameError::BadPointer { offset } => write!(fmtr, "{} at offset {}", self.message(), offset)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DomainNameError {
+    #[inline]
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
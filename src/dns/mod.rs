@@ -0,0 +1,137 @@
+mod domain_name;
+
+pub use self::domain_name::{DomainName, DomainNameError, Labels};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    const WWW_EXAMPLE_COM: &[u8] = b"\x03www\x07example\x03com\x00";
+
+    #[test]
+    fn test_from_str() {
+        let name = DomainName::from_str("www.example.com").unwrap();
+        assert_eq!(name.as_bytes(), WWW_EXAMPLE_COM);
+        assert_eq!(name.wire_len(), 17);
+        assert_eq!(name.labels().collect::<Vec<_>>(), ["www", "example", "com"]);
+        assert_eq!(name.to_string(), "www.example.com.");
+        assert_eq!("www.example.com.".parse::<DomainName>(), Ok(name));
+
+        let root = DomainName::from_str(".").unwrap();
+        assert!(root.is_root());
+        assert_eq!(root.as_bytes(), b"\x00");
+        assert_eq!(root.to_string(), ".");
+        assert_eq!(root, DomainName::default());
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert_eq!(DomainName::from_str(""), Err(DomainNameError::EmptyLabel { offset: 0 }));
+        assert_eq!(DomainName::from_str("www..com"), Err(DomainNameError::EmptyLabel { offset: 4 }));
+        assert_eq!(DomainName::from_str("www.exa*mple.com"), Err(DomainNameError::InvalidCharacter { offset: 7 }));
+        assert_eq!(DomainName::from_str("www.-example.com"), Err(DomainNameError::InvalidCharacter { offset: 4 }));
+        assert_eq!(DomainName::from_str("www.example-.com"), Err(DomainNameError::InvalidCharacter { offset: 11 }));
+        assert!(DomainName::from_str("x-n--1").is_ok());
+
+        let long_label = "a".repeat(64);
+        assert_eq!(DomainName::from_str(&long_label),
+                   Err(DomainNameError::LabelTooLong { required: 64, available: 63 }));
+        let long_name = vec!["a".repeat(63); 4].join(".");
+        assert_eq!(DomainName::from_str(&long_name),
+                   Err(DomainNameError::NameTooLong { required: 257, available: 255 }));
+        assert_eq!(DomainName::from_str(&long_name[..253]).unwrap().wire_len(), 255);
+        #[cfg(feature = "alloc")]
+        assert_eq!(DomainName::from_str("www..com").unwrap_err().to_string(),
+                   "the domain name contains an empty label at offset 4");
+    }
+
+    #[test]
+    fn test_push_label() {
+        use ::ascii::PascalString15;
+
+        let mut name = DomainName::root();
+        name.push_label(&PascalString15::from("Example").unwrap()).unwrap();
+        name.push_label(&PascalString15::from("ORG").unwrap()).unwrap();
+        assert_eq!(name.as_bytes(), b"\x07Example\x03ORG\x00");
+        assert_eq!(name.push_label(&PascalString15::from("a b").unwrap()),
+                   Err(DomainNameError::InvalidCharacter { offset: 1 }));
+        assert_eq!(name.push_label(&PascalString15::new()), Err(DomainNameError::EmptyLabel { offset: 0 }));
+        assert_eq!(name, DomainName::from_str("example.org").unwrap());
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let hash = |name: &DomainName| {
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            hasher.finish()
+        };
+        let lower = DomainName::from_str("www.example.com").unwrap();
+        let mixed = DomainName::from_str("WWW.Example.COM").unwrap();
+        assert_eq!(lower, mixed);
+        assert_eq!(hash(&lower), hash(&mixed));
+        assert_eq!(mixed.to_string(), "WWW.Example.COM.");
+        assert!(lower != DomainName::from_str("www.example.co").unwrap());
+        assert!(lower != DomainName::from_str("www.examplf.com").unwrap());
+    }
+
+    #[test]
+    fn test_from_wire() {
+        let mut bytes = WWW_EXAMPLE_COM.to_vec();
+        bytes.extend_from_slice(b"\x00\x01");
+        let (name, rest) = DomainName::from_wire(&bytes).unwrap();
+        assert_eq!(name, DomainName::from_str("www.example.com").unwrap());
+        assert_eq!(rest, b"\x00\x01");
+
+        assert_eq!(DomainName::from_wire(b"\x03www\x07exam"),
+                   Err(DomainNameError::Truncated { required: 12, available: 9 }));
+        assert_eq!(DomainName::from_wire(b"\x03www"), Err(DomainNameError::Truncated { required: 5, available: 4 }));
+        assert_eq!(DomainName::from_wire(b"\x03w\xe9w\x00"), Err(DomainNameError::InvalidCharacter { offset: 2 }));
+        assert_eq!(DomainName::from_wire(b"\x03a.b\x00"), Err(DomainNameError::InvalidCharacter { offset: 2 }));
+        assert_eq!(DomainName::from_wire(b"\x02a\x00\x00"), Err(DomainNameError::InvalidCharacter { offset: 2 }));
+        assert_eq!(DomainName::from_wire(b"\x04-www\x00"), Err(DomainNameError::InvalidCharacter { offset: 1 }));
+        assert_eq!(DomainName::from_wire(b"\x03www\x40"), Err(DomainNameError::InvalidLabelType { offset: 4 }));
+        assert_eq!(DomainName::from_wire(b"\x03www\xC0\x00"), Err(DomainNameError::BadPointer { offset: 4 }));
+    }
+
+    #[test]
+    fn test_service_names() {
+        let (name, _) = DomainName::from_wire(b"\x04_sip\x04_tcp\x07example\x03com\x00").unwrap();
+        assert_eq!(name.to_string(), "_sip._tcp.example.com.");
+        assert_eq!(name.labels().next().unwrap(), "_sip");
+        assert_eq!(DomainName::from_str("_sip._tcp.example.com").unwrap().as_bytes(), name.as_bytes());
+
+        // The text form parses back to the same name.
+        assert_eq!(DomainName::from_str(name.to_string()).unwrap().as_bytes(), name.as_bytes());
+        let (name, _) = DomainName::from_wire(b"\x06_dmarc\x07EXAMPLE\x03com\x00").unwrap();
+        assert_eq!(name.wire_len(), 20);
+        assert_eq!(DomainName::from_str(name.to_string()).unwrap().as_bytes(), name.as_bytes());
+    }
+
+    #[test]
+    fn test_from_packet() {
+        // A 12 byte header, then "example.com" at offset 12, then "www" followed by a pointer to offset 12.
+        let mut packet = vec![0u8; 12];
+        packet.extend_from_slice(b"\x07example\x03com\x00");
+        packet.extend_from_slice(b"\x03www\xC0\x0C\xFF");
+
+        let (name, end) = DomainName::from_packet(&packet, 12).unwrap();
+        assert_eq!(name, DomainName::from_str("example.com").unwrap());
+        assert_eq!(end, 25);
+        let (name, end) = DomainName::from_packet(&packet, 25).unwrap();
+        assert_eq!(name.as_bytes(), WWW_EXAMPLE_COM);
+        assert_eq!(end, 31);
+
+        // A pointer to itself, and a pointer forwards
+        assert_eq!(DomainName::from_packet(b"\xC0\x00", 0), Err(DomainNameError::BadPointer { offset: 0 }));
+        assert_eq!(DomainName::from_packet(b"\xC0\x02\x00", 0), Err(DomainNameError::BadPointer { offset: 0 }));
+        // Two pointers which point at each other
+        assert_eq!(DomainName::from_packet(b"\x01a\xC0\x04\xC0\x00", 4),
+                   Err(DomainNameError::BadPointer { offset: 2 }));
+        assert_eq!(DomainName::from_packet(&packet[..30], 25),
+                   Err(DomainNameError::Truncated { required: 31, available: 30 }));
+    }
+}
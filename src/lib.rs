@@ -1,5 +1,9 @@
+#![no_std]
 #![warn(missing_docs, trivial_numeric_casts, unused_extern_crates, unused_import_braces, unused_qualifications,
         unused_results)]
+// Struct literals spell out every field name, and the inherent `from_str()` constructors take any `AsRef<str>`
+// alongside the `FromStr` implementations.
+#![allow(clippy::redundant_field_names, clippy::should_implement_trait)]
 
 //! # Pascal strings in Rust.
 //!
@@ -15,9 +19,39 @@
 //! * You need to store your string data inline into your `struct` type - for example if you will allocate a bunch
 //!   of these custom `struct` types into a pool allocator, and cannot afford the heap fragmentation.
 //! * You will keep, allocate, and deallocate a *lot* of short strings in your program.
+//!
+//! # Features
+//!
+//! * `std` (enabled by default): implements the `std::error::Error` and `std::ascii::AsciiExt` traits, and
+//!   reading and writing strings with `std::io`. Implies `alloc`.
+//! * `alloc`: enables conversions into heap allocated types, such as `String` and `Vec<u8>`, and allocating
+//!   methods such as `PascalStr::as_cstr()`.
+//!
+//! Without these features, the crate only depends on `core`, and can be used on targets without an allocator.
+//!
+//! * `serde`: implements `Serialize` and `Deserialize` for the string types in this crate. They are serialized
+//!   as strings in human readable formats, and as bytes in binary formats.
+
+#[cfg(any(feature = "std", test))]
+#[macro_use]
+extern crate std;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 extern crate ascii as ascii_crate;
 extern crate odds;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+
+#[macro_use]
+mod packed;
+#[cfg(feature = "serde")]
+#[macro_use]
+mod serde_impls;
+#[macro_use]
+mod split;
 
 /// Ascii encoded pascal strings.
 pub mod ascii;
@@ -25,4 +59,183 @@ pub mod ascii;
 /// Utf8 encoded pascal strings.
 pub mod utf8;
 
-const PASCAL_STRING_BUF_SIZE: usize = ::std::u8::MAX as usize;
+/// Utf16 encoded pascal strings, which store a length followed by UTF-16 code units.
+pub mod utf16;
+
+/// Pascal strings in single byte legacy encodings, such as Latin-1 and Mac OS Roman.
+///
+/// Strings from Turbo Pascal, Delphi and classic Mac OS programs are rarely pure ascii. This module stores
+/// one byte per character in a single byte encoding chosen by the `Encoding` parameter `E`, and decodes
+/// losslessly to `char`s.
+///
+/// ```
+/// use pascal_string::codepage::{MacRomanString, PascalStringCreateError};
+///
+/// let string: MacRomanString<15> = MacRomanString::from_str("Café").unwrap();
+/// assert_eq!(string.as_bytes(), b"Caf\x8e");
+/// assert_eq!(string, "Café");
+///
+/// let error = MacRomanString::<15>::from_str("Ça coûte 5 ₽").unwrap_err();
+/// match error {
+///     PascalStringCreateError::Unencodable(e) => assert_eq!((e.character(), e.index()), ('₽', 13)),
+///     _ => unreachable!()
+/// }
+/// ```
+pub mod codepage;
+
+/// DNS domain names, which are stored on the wire as a sequence of ascii pascal strings.
+pub mod dns;
+
+/// Delphi and Turbo Pascal `ShortString` record fields, and reading and writing `file of record` streams.
+pub mod delphi;
+
+/// Fixed size strings from the classic Mac OS Toolbox, such as `Str255` and `Str31`.
+pub mod mac;
+
+/// Length prefixes for pascal strings.
+pub mod prefix;
+
+use core::marker::PhantomData;
+use core::ops::{Bound, Range, RangeBounds};
+use prefix::LengthPrefix;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+const PASCAL_STRING_BUF_SIZE: usize = u8::MAX as usize;
+
+/// The largest `LengthPrefix::WIDTH` which can be read or written using `std::io`.
+#[cfg(feature = "std")]
+const MAX_PREFIX_WIDTH: usize = 8;
+
+/// Describes how a `PascalString` is laid out when it is read from or written to a stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// The length prefix, followed by only the bytes which are used by the string.
+    Compact,
+    /// The length prefix, followed by as many bytes as the capacity of the string. Bytes past the end of
+    /// the string are written as zeros, and ignored when read. For the default capacity, this is the
+    /// traditional 256 byte record.
+    Record
+}
+
+/// Describes the input which was lost when a `PascalString` was created by one of its lossy constructors, such
+/// as `from_truncated()` or `from_lossy()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConversionLoss {
+    truncated: usize,
+    replaced: usize
+}
+
+impl ConversionLoss {
+    /// The number of bytes at the end of the input which were dropped because they did not fit into the
+    /// string.
+    #[inline]
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    /// The number of characters or bytes in the input which could not be represented in the string, and were
+    /// replaced with a replacement character.
+    #[inline]
+    pub fn replaced(&self) -> usize {
+        self.replaced
+    }
+
+    /// Returns true if the whole input was stored in the string unchanged.
+    #[inline]
+    pub fn is_lossless(&self) -> bool {
+        self.truncated == 0 && self.replaced == 0
+    }
+}
+
+/// Compile-time checks on the capacity of a `PascalString`.
+///
+/// Evaluating `CapacityCheck::<L, N>::VALID` fails to compile if a length of `N` cannot be stored in the
+/// length prefix `L`.
+struct CapacityCheck<L, const N: usize>(PhantomData<L>);
+
+impl<L: LengthPrefix, const N: usize> CapacityCheck<L, N> {
+    const VALID: () = assert!(N <= L::MAX_LEN, "the capacity of a PascalString cannot exceed its length prefix");
+}
+
+/// Compile-time check that an array of `M` bytes can hold a length prefix `L` followed by `N` bytes of data.
+struct ArrayLenCheck<L, const N: usize, const M: usize>(PhantomData<L>);
+
+impl<L: LengthPrefix, const N: usize, const M: usize> ArrayLenCheck<L, N, M> {
+    const VALID: () = assert!(M == N + L::WIDTH, "the array must be large enough to hold the prefix and the string");
+}
+
+/// Compile-time check that an array of `M` bytes can hold a length prefix `L` followed by `N` UTF-16 code units.
+struct UnitArrayLenCheck<L, const N: usize, const M: usize>(PhantomData<L>);
+
+impl<L: LengthPrefix, const N: usize, const M: usize> UnitArrayLenCheck<L, N, M> {
+    const VALID: () = assert!(M == 2 * N + L::WIDTH,
+                              "the array must be large enough to hold the prefix and the code units");
+}
+
+/// Resolve `range` into the indices it covers in a string of length `len`.
+///
+/// # Panics
+///
+/// Panics if the range starts after it ends, or if it ends after `len`.
+fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len
+    };
+    assert!(start <= end, "range start {} is greater than range end {}", start, end);
+    assert!(end <= len, "range end {} is out of bounds for a string of length {}", end, len);
+    start..end
+}
+
+/// Write a length prefix of type `L`, followed by `bytes`, to `writer`. If `layout` is `Layout::Record`, the
+/// output is padded with zeros until `capacity` bytes of string data have been written.
+#[cfg(feature = "std")]
+fn write_prefixed<L: LengthPrefix, W: Write>(writer: &mut W, bytes: &[u8], capacity: usize, layout: Layout)
+                                            -> io::Result<()> {
+    write_prefix::<L, W>(writer, bytes.len())?;
+    writer.write_all(bytes)?;
+    if layout == Layout::Record {
+        let padding = [0u8; 32];
+        let mut remaining = capacity - bytes.len();
+        while remaining > 0 {
+            let chunk = ::core::cmp::min(remaining, padding.len());
+            writer.write_all(&padding[..chunk])?;
+            remaining -= chunk;
+        }
+    }
+    Ok(())
+}
+
+/// Write a length prefix of type `L`, which holds `len`, to `writer`.
+#[cfg(feature = "std")]
+fn write_prefix<L: LengthPrefix, W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let mut prefix = [0u8; MAX_PREFIX_WIDTH];
+    L::write_len(L::from_usize(len), &mut prefix[..L::WIDTH]);
+    writer.write_all(&prefix[..L::WIDTH])
+}
+
+/// Read a length prefix of type `L` from `reader`.
+#[cfg(feature = "std")]
+fn read_prefix<L: LengthPrefix, R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut prefix = [0u8; MAX_PREFIX_WIDTH];
+    reader.read_exact(&mut prefix[..L::WIDTH])?;
+    Ok(L::to_usize(L::read_len(&prefix)))
+}
+
+/// Read and discard `count` bytes from `reader`.
+#[cfg(feature = "std")]
+fn skip_bytes<R: Read>(reader: &mut R, count: usize) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(count as u64), &mut io::sink())?;
+    if skipped < count as u64 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+    }
+    Ok(())
+}
+
@@ -0,0 +1,66 @@
+use core::convert::TryFrom;
+use ::ascii;
+use ::codepage::{self, MacRoman};
+use ::delphi::ShortString;
+use ::utf8;
+
+/// The Toolbox `Str255` type, which is 256 bytes long.
+pub type Str255 = ShortString<MacRoman, 255>;
+
+/// The Toolbox `Str63` type, which is 64 bytes long.
+pub type Str63 = ShortString<MacRoman, 63>;
+
+/// The Toolbox `Str31` type, which is 32 bytes long. It is used for file and volume names.
+pub type Str31 = ShortString<MacRoman, 31>;
+
+/// The Toolbox `Str27` type, which is 28 bytes long. It is used for the names of file system objects in
+/// some older structures.
+pub type Str27 = ShortString<MacRoman, 27>;
+
+/// Toolbox strings have the same layout as a Delphi `string[N]`: a single length byte followed by `N` bytes of
+/// Mac OS Roman encoded characters, with no padding.
+impl<const N: usize> ShortString<MacRoman, N> {
+    /// Converts this string into a `utf8::PascalString`.
+    ///
+    /// Returns an `Err` if the utf8 encoding of the string is longer than the capacity `M`. Characters outside
+    /// of ascii take up two or three utf8 bytes, so this can fail even if `M` is equal to `N`.
+    pub fn to_utf8<const M: usize>(&self) -> Result<utf8::PascalString<M>, utf8::PascalStringCreateError> {
+        let mut string = utf8::PascalString::new();
+        let mut required = 0;
+        for ch in self.chars() {
+            required += ch.len_utf8();
+            if required <= M {
+                string.push(ch);
+            }
+        }
+        if required > M {
+            return Err(utf8::PascalStringCreateError::InputTooLong { required: required, available: M });
+        }
+        Ok(string)
+    }
+}
+
+impl<const N: usize> From<ascii::PascalString<N>> for ShortString<MacRoman, N> {
+    /// Mac OS Roman is a superset of ascii, so this conversion cannot fail.
+    #[inline]
+    fn from(string: ascii::PascalString<N>) -> Self {
+        let bytes: &[u8] = string.as_ref();
+        codepage::PascalString::<MacRoman, N>::from_bytes(bytes).unwrap().into()
+    }
+}
+
+impl<const N: usize> TryFrom<ShortString<MacRoman, N>> for ascii::PascalString<N> {
+    type Error = ascii::AsciiError;
+
+    /// Converts a Toolbox string into an ascii `PascalString` of the same capacity.
+    ///
+    /// Returns an `Err` if the string contains any characters outside of ascii.
+    #[inline]
+    fn try_from(string: ShortString<MacRoman, N>) -> Result<Self, ascii::AsciiError> {
+        match ascii::PascalString::from(string.as_bytes()) {
+            Ok(ascii) => Ok(ascii),
+            Err(ascii::PascalStringCreateError::NotValidAscii(e)) => Err(e),
+            Err(ascii::PascalStringCreateError::InputTooLong { .. }) => unreachable!()
+        }
+    }
+}
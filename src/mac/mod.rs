@@ -0,0 +1,63 @@
+mod mac_str;
+
+pub use self::mac_str::{Str255, Str27, Str31, Str63};
+pub use ::delphi::StaleBytes;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use std::prelude::v1::*;
+    use ::ascii;
+    use ::codepage::{MacRomanString, PascalStringCreateError};
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(size_of::<Str255>(), 256);
+        assert_eq!(size_of::<Str63>(), 64);
+        assert_eq!(size_of::<Str31>(), 32);
+        assert_eq!(size_of::<Str27>(), 28);
+        assert_eq!(align_of::<Str27>(), 1);
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let mut raw = [0xAAu8; 28];
+        raw[0] = 5;
+        raw[1..6].copy_from_slice(b"Caf\x8e!");
+        let name = Str27::from_array(raw, StaleBytes::Keep).unwrap();
+        assert_eq!(name.len(), 5);
+        assert_eq!(name, "Café!");
+        assert_eq!(name.to_string(), "Café!");
+        #[cfg(feature = "alloc")]
+        assert_eq!(Into::<String>::into(name), "Café!");
+        // The stale bytes past the end of the string are kept
+        assert_eq!(name.to_array(), raw);
+
+        raw[0] = 28;
+        assert_eq!(Str27::from_array(raw, StaleBytes::Keep),
+                   Err(PascalStringCreateError::InputTooLong { required: 28, available: 27 }));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let volume = Str31::from_str("Macintosh HD™").unwrap();
+        assert_eq!(volume.as_bytes(), b"Macintosh HD\xaa");
+        assert!(Str31::from_str("₽").is_err());
+        assert_eq!("Macintosh HD™".parse::<Str31>(), Ok(volume));
+
+        let mac_roman = MacRomanString::<31>::from(volume);
+        assert_eq!(mac_roman, "Macintosh HD™");
+        assert_eq!(Str31::from(mac_roman), volume);
+
+        assert_eq!(volume.to_utf8::<15>().unwrap(), "Macintosh HD™");
+        assert_eq!(volume.to_utf8::<14>(),
+                   Err(::utf8::PascalStringCreateError::InputTooLong { required: 15, available: 14 }));
+
+        assert!(ascii::PascalString::<31>::try_from(volume).is_err());
+        let ascii = ascii::PascalString::<31>::from("System Folder").unwrap();
+        let folder = Str31::from(ascii.clone());
+        assert_eq!(folder, "System Folder");
+        assert_eq!(ascii::PascalString::<31>::try_from(folder), Ok(ascii));
+    }
+}
@@ -0,0 +1,236 @@
+/// Defines `PackedPascalStrings` and `PackedPascalStringsWriter` for the `PascalStr` of `$module`.
+///
+/// The packed string types only depend on the length prefix and the byte representation of a string, so they are
+/// the same for every encoding apart from the type of string which they yield.
+macro_rules! packed_pascal_strings {
+    ($module:ident) => {
+        #[cfg(feature = "alloc")]
+        use alloc::vec::Vec;
+        use core::iter::Iterator;
+        use core::marker::PhantomData;
+        use ::$module::{PascalStr, PascalStringAppendError, PrefixedBytesError};
+        use ::prefix::{LengthPrefix, U8};
+
+        /// An iterator over a sequence of back-to-back pascal strings stored in a byte slice.
+        ///
+        /// Many legacy formats (such as Mac `STR#` resources, Delphi string tables and DNS names) store a
+        /// count or a terminator along with a run of length prefixed strings. Each string is borrowed from the
+        /// underlying buffer as a `PascalStr`, without being copied.
+        ///
+        /// By default, strings are read until the end of the buffer. Use `with_count()`, `read_count()` or
+        /// `until_empty()` to stop earlier, and `remainder()` to get the bytes which follow the sequence.
+        #[derive(Clone, Debug)]
+        pub struct PackedPascalStrings<'a, L: LengthPrefix = U8> {
+            bytes: &'a [u8],
+            until: Until,
+            finished: bool,
+            _prefix: PhantomData<L>
+        }
+
+        /// Describes where a sequence of packed pascal strings ends.
+        #[derive(Clone, Copy, Debug)]
+        enum Until {
+            /// The sequence ends at the end of the buffer.
+            End,
+            /// The sequence contains this many more strings.
+            Count(usize),
+            /// The sequence ends at the first empty string.
+            Empty
+        }
+
+        impl<'a> PackedPascalStrings<'a> {
+            /// Create a new iterator over the single byte length prefixed strings in `bytes`.
+            #[inline]
+            pub fn new(bytes: &'a [u8]) -> Self {
+                PackedPascalStrings {
+                    bytes: bytes,
+                    until: Until::End,
+                    finished: false,
+                    _prefix: PhantomData
+                }
+            }
+        }
+
+        impl<'a, L: LengthPrefix> PackedPascalStrings<'a, L> {
+            /// Read the strings using the length prefix `M` instead.
+            #[inline]
+            pub fn with_prefix<M: LengthPrefix>(self) -> PackedPascalStrings<'a, M> {
+                PackedPascalStrings {
+                    bytes: self.bytes,
+                    until: self.until,
+                    finished: self.finished,
+                    _prefix: PhantomData
+                }
+            }
+
+            /// Stop iterating after `count` strings have been read.
+            #[inline]
+            pub fn with_count(mut self, count: usize) -> Self {
+                self.until = Until::Count(count);
+                self
+            }
+
+            /// Read the number of strings in the sequence from the start of the buffer, using the integer
+            /// format `C`, and stop iterating once that many strings have been read.
+            ///
+            /// Returns an `Err` if the buffer is too short to hold the count.
+            pub fn read_count<C: LengthPrefix>(mut self) -> Result<Self, PrefixedBytesError> {
+                if self.bytes.len() < C::WIDTH {
+                    return Err(PrefixedBytesError::Truncated { required: C::WIDTH, available: self.bytes.len() });
+                }
+                let count = C::to_usize(C::read_len(self.bytes));
+                self.bytes = &self.bytes[C::WIDTH..];
+                Ok(self.with_count(count))
+            }
+
+            /// Stop iterating when an empty string is read. The empty string is consumed, but not returned.
+            #[inline]
+            pub fn until_empty(mut self) -> Self {
+                self.until = Until::Empty;
+                self
+            }
+
+            /// Returns the part of the buffer which has not been read yet.
+            #[inline]
+            pub fn remainder(&self) -> &'a [u8] {
+                self.bytes
+            }
+        }
+
+        impl<'a, L: LengthPrefix> Iterator for PackedPascalStrings<'a, L> {
+            type Item = Result<&'a PascalStr, PrefixedBytesError>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.finished {
+                    return None;
+                }
+                match self.until {
+                    Until::End if self.bytes.is_empty() => return None,
+                    Until::Count(0) => return None,
+                    _ => ()
+                }
+
+                let (string, rest) = match PascalStr::from_prefixed_bytes_with::<L>(self.bytes) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        self.finished = true;
+                        return Some(Err(e));
+                    }
+                };
+                self.bytes = rest;
+                match self.until {
+                    Until::Count(ref mut count) => *count -= 1,
+                    Until::Empty if string.is_empty() => {
+                        self.finished = true;
+                        return None;
+                    }
+                    _ => ()
+                }
+                Some(Ok(string))
+            }
+        }
+
+        /// Writes a sequence of back-to-back pascal strings into a buffer.
+        ///
+        /// The buffer `B` can either be a `&mut Vec<u8>`, in which case the strings are appended to the end of the
+        /// vector, or a `&mut [u8]`, in which case the strings are written from the start of the slice.
+        #[derive(Debug)]
+        pub struct PackedPascalStringsWriter<B, L: LengthPrefix = U8> {
+            buffer: B,
+            written: usize,
+            _prefix: PhantomData<L>
+        }
+
+        impl<B> PackedPascalStringsWriter<B> {
+            /// Create a new writer, which writes single byte length prefixed strings into `buffer`.
+            #[inline]
+            pub fn new(buffer: B) -> Self {
+                PackedPascalStringsWriter {
+                    buffer: buffer,
+                    written: 0,
+                    _prefix: PhantomData
+                }
+            }
+        }
+
+        impl<B, L: LengthPrefix> PackedPascalStringsWriter<B, L> {
+            /// Write the strings using the length prefix `M` instead.
+            #[inline]
+            pub fn with_prefix<M: LengthPrefix>(self) -> PackedPascalStringsWriter<B, M> {
+                PackedPascalStringsWriter {
+                    buffer: self.buffer,
+                    written: self.written,
+                    _prefix: PhantomData
+                }
+            }
+
+            /// Returns the number of bytes which have been written by this writer.
+            #[inline]
+            pub fn written(&self) -> usize {
+                self.written
+            }
+
+            /// Consumes the writer, returning the underlying buffer.
+            #[inline]
+            pub fn into_inner(self) -> B {
+                self.buffer
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<'a, L: LengthPrefix> PackedPascalStringsWriter<&'a mut Vec<u8>, L> {
+            /// Append a string onto the end of the vector.
+            ///
+            /// Returns `Err(_)` if the string is too long to be stored with the length prefix `L`.
+            pub fn push<S: AsRef<PascalStr>>(&mut self, string: S) -> Result<(), PascalStringAppendError> {
+                let bytes: &[u8] = string.as_ref().as_ref();
+                if bytes.len() > L::MAX_LEN {
+                    return Err(PascalStringAppendError::NoRoom { required: bytes.len(), available: L::MAX_LEN });
+                }
+                let start = self.buffer.len();
+                self.buffer.resize(start + L::WIDTH, 0);
+                L::write_len(L::from_usize(bytes.len()), &mut self.buffer[start..]);
+                self.buffer.extend_from_slice(bytes);
+                self.written += L::WIDTH + bytes.len();
+                Ok(())
+            }
+
+            /// Append an empty string onto the end of the vector, to terminate the sequence.
+            #[inline]
+            pub fn push_terminator(&mut self) -> Result<(), PascalStringAppendError> {
+                self.push(<&PascalStr>::default())
+            }
+        }
+
+        impl<'a, L: LengthPrefix> PackedPascalStringsWriter<&'a mut [u8], L> {
+            /// Write a string into the slice, after the strings which have already been written.
+            ///
+            /// Returns `Err(_)` if there is no room left in the slice, or if the string is too long to be stored
+            /// with the length prefix `L`.
+            pub fn push<S: AsRef<PascalStr>>(&mut self, string: S) -> Result<(), PascalStringAppendError> {
+                let bytes: &[u8] = string.as_ref().as_ref();
+                let start = self.written;
+                let end = start + L::WIDTH + bytes.len();
+                if bytes.len() > L::MAX_LEN {
+                    return Err(PascalStringAppendError::NoRoom { required: bytes.len(), available: L::MAX_LEN });
+                }
+                if end > self.buffer.len() {
+                    return Err(PascalStringAppendError::NoRoom {
+                        required: end - start,
+                        available: self.buffer.len() - start
+                    });
+                }
+                L::write_len(L::from_usize(bytes.len()), &mut self.buffer[start..]);
+                self.buffer[start + L::WIDTH..end].copy_from_slice(bytes);
+                self.written = end;
+                Ok(())
+            }
+
+            /// Write an empty string into the slice, to terminate the sequence.
+            #[inline]
+            pub fn push_terminator(&mut self) -> Result<(), PascalStringAppendError> {
+                self.push(<&PascalStr>::default())
+            }
+        }
+    }
+}
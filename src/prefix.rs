@@ -0,0 +1,139 @@
+use core::fmt::Debug;
+use core::hash::Hash;
+
+/// Describes how the length of a pascal string is stored.
+///
+/// A traditional pascal string stores its length in a single byte, which limits it to 255 characters. Many
+/// binary formats use wider length prefixes instead - for example, Java's `DataOutput.writeUTF` uses a big
+/// endian `u16`, and SSH strings use a big endian `u32`. The types in this module can be used as the `L`
+/// parameter of `ascii::PascalString` and `utf8::PascalString` to select the width and byte order of the
+/// length prefix.
+///
+/// In memory, the length is stored using `Self::Len` in native byte order. The byte order only applies when the
+/// string is serialized, for example with `PascalString::to_array()`.
+pub trait LengthPrefix {
+    /// The integer type used to store the length of the string in memory.
+    type Len: Copy + Default + Debug + Eq + Ord + Hash;
+
+    /// The number of bytes the length prefix takes up when serialized.
+    ///
+    /// This may not be larger than 8.
+    const WIDTH: usize;
+
+    /// The largest length which can be stored in this prefix.
+    const MAX_LEN: usize;
+
+    /// The stored length of an empty string.
+    const EMPTY: Self::Len;
+
+    /// Convert a stored length to a `usize`.
+    fn to_usize(len: Self::Len) -> usize;
+
+    /// Convert a `usize` into a stored length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is larger than `Self::MAX_LEN`.
+    fn from_usize(len: usize) -> Self::Len;
+
+    /// Write `len` into the first `Self::WIDTH` bytes of `buf`, using the byte order of this prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than `Self::WIDTH`.
+    fn write_len(len: Self::Len, buf: &mut [u8]);
+
+    /// Read a length from the first `Self::WIDTH` bytes of `buf`, using the byte order of this prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than `Self::WIDTH`.
+    fn read_len(buf: &[u8]) -> Self::Len;
+}
+
+/// A single byte length prefix. This is the traditional pascal string layout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U8;
+
+/// A two byte, big endian length prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U16BE;
+
+/// A two byte, little endian length prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U16LE;
+
+/// A four byte, big endian length prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U32BE;
+
+/// A four byte, little endian length prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U32LE;
+
+impl LengthPrefix for U8 {
+    type Len = u8;
+    const WIDTH: usize = 1;
+    const MAX_LEN: usize = u8::MAX as usize;
+    const EMPTY: u8 = 0;
+
+    #[inline]
+    fn to_usize(len: u8) -> usize {
+        len as usize
+    }
+
+    #[inline]
+    fn from_usize(len: usize) -> u8 {
+        assert!(len <= Self::MAX_LEN);
+        len as u8
+    }
+
+    #[inline]
+    fn write_len(len: u8, buf: &mut [u8]) {
+        buf[0] = len;
+    }
+
+    #[inline]
+    fn read_len(buf: &[u8]) -> u8 {
+        buf[0]
+    }
+}
+
+macro_rules! impl_length_prefix {
+    ($prefix:ident, $len:ident, $width:expr, $to_bytes:ident, $from_bytes:ident) => {
+        impl LengthPrefix for $prefix {
+            type Len = $len;
+            const WIDTH: usize = $width;
+            const MAX_LEN: usize = $len::MAX as usize;
+            const EMPTY: $len = 0;
+
+            #[inline]
+            fn to_usize(len: $len) -> usize {
+                len as usize
+            }
+
+            #[inline]
+            fn from_usize(len: usize) -> $len {
+                assert!(len <= Self::MAX_LEN);
+                len as $len
+            }
+
+            #[inline]
+            fn write_len(len: $len, buf: &mut [u8]) {
+                buf[..$width].copy_from_slice(&len.$to_bytes());
+            }
+
+            #[inline]
+            fn read_len(buf: &[u8]) -> $len {
+                let mut bytes = [0u8; $width];
+                bytes.copy_from_slice(&buf[..$width]);
+                $len::$from_bytes(bytes)
+            }
+        }
+    }
+}
+
+impl_length_prefix!(U16BE, u16, 2, to_be_bytes, from_be_bytes);
+impl_length_prefix!(U16LE, u16, 2, to_le_bytes, from_le_bytes);
+impl_length_prefix!(U32BE, u32, 4, to_be_bytes, from_be_bytes);
+impl_length_prefix!(U32LE, u32, 4, to_le_bytes, from_le_bytes);
@@ -0,0 +1,82 @@
+/// Implements `Serialize` and `Deserialize` for the `PascalStr` and `PascalString` of `$module`.
+///
+/// Bytes are converted into a `PascalString` with the constructor `$from_bytes`, which is documented with
+/// `$deserialize_doc`. `$expecting` is formatted with the capacity of the string.
+macro_rules! serde_impls {
+    ($module:ident, $from_bytes:ident, $deserialize_doc:expr, $expecting:literal) => {
+        use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+        use serde::ser::{Serialize, Serializer};
+        use core::fmt;
+        use core::marker::PhantomData;
+        use ::$module::{PascalStr, PascalString, PascalStringCreateError};
+        use ::prefix::LengthPrefix;
+
+        /// Serialized as a string in human readable formats, and as bytes otherwise.
+        impl Serialize for PascalStr {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(self.as_str())
+                } else {
+                    serializer.serialize_bytes(self.as_ref())
+                }
+            }
+        }
+
+        /// Serialized as a string in human readable formats, and as bytes otherwise.
+        impl<const N: usize, L: LengthPrefix> Serialize for PascalString<N, L> {
+            #[inline]
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                (**self).serialize(serializer)
+            }
+        }
+
+        #[doc = $deserialize_doc]
+        impl<'de, const N: usize, L: LengthPrefix> Deserialize<'de> for PascalString<N, L> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(PascalStringVisitor(PhantomData))
+                } else {
+                    deserializer.deserialize_bytes(PascalStringVisitor(PhantomData))
+                }
+            }
+        }
+
+        struct PascalStringVisitor<const N: usize, L: LengthPrefix>(PhantomData<L>);
+
+        impl<'de, const N: usize, L: LengthPrefix> Visitor<'de> for PascalStringVisitor<N, L> {
+            type Value = PascalString<N, L>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, $expecting, N)
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                self.visit_bytes(v.as_bytes())
+            }
+
+            #[inline]
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                PascalString::$from_bytes(v).map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = [0u8; N];
+                let mut len = 0;
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    if len < N {
+                        bytes[len] = byte;
+                    }
+                    len += 1;
+                }
+                if len > N {
+                    return Err(de::Error::custom(PascalStringCreateError::InputTooLong {
+                        required: len,
+                        available: N
+                    }));
+                }
+                self.visit_bytes(&bytes[..len])
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+/// Defines the split iterators for the `PascalStr` of `$module`, which splits a `$haystack` into pieces using the
+/// `Searcher` of `$module`.
+///
+/// `SplitWhitespace` splits the string at the characters `$ch` for which `$is_whitespace` returns true, and is
+/// documented with `$whitespace_doc`.
+macro_rules! split_iterators {
+    ($module:ident, $haystack:ty, $ch:ty, $is_whitespace:ident, $whitespace_doc:expr) => {
+        use core::iter::Iterator;
+        use ::$module::{PascalStr, Pattern};
+        use ::$module::pattern::Searcher;
+
+        /// The state shared by the split iterators.
+        #[derive(Clone, Debug)]
+        struct SplitInternal<'a, P> {
+            searcher: Searcher<'a, P>,
+            /// The start of the part of the string which has not been yielded yet.
+            start: usize,
+            /// The end of the part of the string which has not been yielded yet.
+            end: usize,
+            /// Whether an empty piece at the end of the string should be yielded.
+            allow_trailing_empty: bool,
+            finished: bool
+        }
+
+        impl<'a, P: Pattern> SplitInternal<'a, P> {
+            #[inline]
+            fn new(haystack: &'a $haystack, pattern: P, allow_trailing_empty: bool) -> Self {
+                SplitInternal {
+                    searcher: Searcher::new(haystack, pattern),
+                    start: 0,
+                    end: haystack.len(),
+                    allow_trailing_empty: allow_trailing_empty,
+                    finished: false
+                }
+            }
+
+            #[inline]
+            fn slice(&self, start: usize, end: usize) -> &'a PascalStr {
+                From::from(&self.searcher.haystack()[start..end])
+            }
+
+            #[inline]
+            fn remainder(&mut self) -> Option<&'a PascalStr> {
+                if self.finished {
+                    return None;
+                }
+                self.finished = true;
+                if self.allow_trailing_empty || self.end > self.start {
+                    Some(self.slice(self.start, self.end))
+                } else {
+                    None
+                }
+            }
+
+            fn next(&mut self) -> Option<&'a PascalStr> {
+                if self.finished {
+                    return None;
+                }
+                match self.searcher.next_match() {
+                    Some((index, len)) => {
+                        let piece = self.slice(self.start, index);
+                        self.start = index + len;
+                        Some(piece)
+                    }
+                    None => self.remainder()
+                }
+            }
+
+            fn next_back(&mut self) -> Option<&'a PascalStr> {
+                if self.finished {
+                    return None;
+                }
+                match self.searcher.next_match_back() {
+                    Some((index, len)) => {
+                        let piece = self.slice(index + len, self.end);
+                        self.end = index;
+                        Some(piece)
+                    }
+                    None => self.remainder()
+                }
+            }
+        }
+
+        /// An iterator over the parts of a `PascalStr` separated by a pattern.
+        ///
+        /// This is created by `PascalStr::split()`.
+        #[derive(Clone, Debug)]
+        pub struct Split<'a, P>(SplitInternal<'a, P>);
+
+        impl<'a, P: Pattern> Split<'a, P> {
+            #[inline]
+            pub(crate) fn new(haystack: &'a $haystack, pattern: P) -> Self {
+                Split(SplitInternal::new(haystack, pattern, true))
+            }
+        }
+
+        impl<'a, P: Pattern> Iterator for Split<'a, P> {
+            type Item = &'a PascalStr;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next()
+            }
+        }
+
+        /// An iterator over the parts of a `PascalStr` separated by a pattern, starting from the end of the string.
+        ///
+        /// This is created by `PascalStr::rsplit()`.
+        #[derive(Clone, Debug)]
+        pub struct RSplit<'a, P>(SplitInternal<'a, P>);
+
+        impl<'a, P: Pattern> RSplit<'a, P> {
+            #[inline]
+            pub(crate) fn new(haystack: &'a $haystack, pattern: P) -> Self {
+                RSplit(SplitInternal::new(haystack, pattern, true))
+            }
+        }
+
+        impl<'a, P: Pattern> Iterator for RSplit<'a, P> {
+            type Item = &'a PascalStr;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next_back()
+            }
+        }
+
+        /// An iterator over at most `n` parts of a `PascalStr` separated by a pattern.
+        ///
+        /// This is created by `PascalStr::splitn()`.
+        #[derive(Clone, Debug)]
+        pub struct SplitN<'a, P> {
+            inner: SplitInternal<'a, P>,
+            count: usize
+        }
+
+        impl<'a, P: Pattern> SplitN<'a, P> {
+            #[inline]
+            pub(crate) fn new(haystack: &'a $haystack, pattern: P, count: usize) -> Self {
+                SplitN {
+                    inner: SplitInternal::new(haystack, pattern, true),
+                    count: count
+                }
+            }
+        }
+
+        impl<'a, P: Pattern> Iterator for SplitN<'a, P> {
+            type Item = &'a PascalStr;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                match self.count {
+                    0 => None,
+                    1 => {
+                        self.count = 0;
+                        self.inner.remainder()
+                    }
+                    _ => {
+                        self.count -= 1;
+                        self.inner.next()
+                    }
+                }
+            }
+        }
+
+        /// An iterator over the parts of a `PascalStr` which are each terminated by a pattern.
+        ///
+        /// This is created by `PascalStr::split_terminator()`.
+        #[derive(Clone, Debug)]
+        pub struct SplitTerminator<'a, P>(SplitInternal<'a, P>);
+
+        impl<'a, P: Pattern> SplitTerminator<'a, P> {
+            #[inline]
+            pub(crate) fn new(haystack: &'a $haystack, pattern: P) -> Self {
+                SplitTerminator(SplitInternal::new(haystack, pattern, false))
+            }
+        }
+
+        impl<'a, P: Pattern> Iterator for SplitTerminator<'a, P> {
+            type Item = &'a PascalStr;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next()
+            }
+        }
+
+        #[doc = $whitespace_doc]
+        ///
+        /// This is created by `PascalStr::split_whitespace()`.
+        #[derive(Clone, Debug)]
+        pub struct SplitWhitespace<'a>(Split<'a, fn($ch) -> bool>);
+
+        impl<'a> SplitWhitespace<'a> {
+            #[inline]
+            pub(crate) fn new(haystack: &'a $haystack) -> Self {
+                fn is_whitespace(ch: $ch) -> bool {
+                    ch.$is_whitespace()
+                }
+                SplitWhitespace(Split::new(haystack, is_whitespace))
+            }
+        }
+
+        impl<'a> Iterator for SplitWhitespace<'a> {
+            type Item = &'a PascalStr;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.find(|piece| !piece.is_empty())
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+mod pascal_str;
+mod pascal_string;
+
+pub use self::pascal_str::{Chars, PascalStr, Utf16Error};
+pub use self::pascal_string::{ByteOrder, PascalString, PascalStringAppendError, PascalStringCreateError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use std::prelude::v1::*;
+    #[cfg(feature = "std")]
+    use std::io::ErrorKind;
+    #[cfg(feature = "std")]
+    use ::prefix::U16BE;
+    use ::prefix::U16LE;
+    use ::utf8;
+    #[cfg(feature = "std")]
+    use ::Layout;
+
+    #[test]
+    fn test_string_creation() {
+        let string = PascalString::<15>::from_str("señor 🦀").unwrap();
+        assert_eq!(string.len(), 8);
+        assert_eq!(string.as_units(), &[0x73, 0x65, 0xf1, 0x6f, 0x72, 0x20, 0xd83e, 0xdd80]);
+        assert_eq!(string, "señor 🦀");
+        assert_eq!(string.chars().rev().collect::<String>(), "🦀 roñes");
+        assert_eq!(PascalString::<7>::from_str("señor 🦀"),
+                   Err(PascalStringCreateError::InputTooLong { required: 8, available: 7 }));
+
+        let units = PascalString::<15>::from_units(string.as_units()).unwrap();
+        assert_eq!(units, string);
+        let error = PascalString::<15>::from_units([0x61, 0xdd80, 0x62]).unwrap_err();
+        match error {
+            PascalStringCreateError::NotValidUtf16(e) => {
+                assert_eq!((e.valid_up_to(), e.unpaired_surrogate()), (1, 0xdd80));
+            }
+            _ => unreachable!()
+        }
+        assert!(PascalStr::from_units(&[0xd83e]).is_err());
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut string = PascalString::<4, U16LE>::new();
+        string.push('a');
+        string.push('🦀');
+        assert_eq!(string.try_push('🦀'), Err(PascalStringAppendError::NoRoom { required: 2, available: 1 }));
+        assert_eq!(string.try_push_str("bc"), Err(PascalStringAppendError::NoRoom { required: 2, available: 1 }));
+        assert_eq!(string, "a🦀");
+        string.push_str("b");
+        assert!(string.is_full());
+        assert_eq!(string.pop(), Some('b'));
+        assert_eq!(string.pop(), Some('🦀'));
+        assert_eq!(string.len(), 1);
+        string.clear();
+        assert_eq!(string.pop(), None);
+    }
+
+    #[test]
+    fn test_surrogate_indexing() {
+        let string = PascalString::<15>::from_str("a🦀b").unwrap();
+        assert!(string.is_char_boundary(1));
+        assert!(!string.is_char_boundary(2));
+        assert!(string.is_char_boundary(4));
+        assert!(!string.is_char_boundary(5));
+        assert_eq!(string.char_at(1), Some('🦀'));
+        assert_eq!(string.char_at(2), None);
+        assert_eq!(string.char_at(3), Some('b'));
+        assert_eq!(&string[1..3], "🦀");
+        assert_eq!(&string[3..], "b");
+        assert!(string[..1] < string[1..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_inside_surrogate_pair() {
+        let string = PascalString::<15>::from_str("a🦀b").unwrap();
+        let _ = &string[..2];
+    }
+
+    #[test]
+    fn test_utf8_conversion() {
+        let string = utf8::PascalString15::from_str("año 🦀").unwrap();
+        let utf16 = PascalString::from(string.clone());
+        assert_eq!(utf16, "año 🦀");
+        assert_eq!(utf16.len(), 6);
+        assert_eq!(utf8::PascalString15::try_from(utf16.clone()), Ok(string));
+
+        let utf16 = PascalString::<15>::from_str("€€€€€€").unwrap();
+        assert_eq!(utf8::PascalString15::try_from(utf16.clone()),
+                   Err(utf8::PascalStringCreateError::InputTooLong { required: 18, available: 15 }));
+        let larger: utf8::PascalString31 = utf16.to_utf8().unwrap();
+        assert_eq!(larger, "€€€€€€");
+        assert_eq!(PascalString::<15>::from_utf8(&*larger).unwrap(), utf16);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_write() {
+        let string = PascalString::<7, U16LE>::from_str("a🦀").unwrap();
+        let mut bytes = Vec::new();
+        string.write_to(&mut bytes, Layout::Compact, ByteOrder::LittleEndian).unwrap();
+        assert_eq!(bytes, b"\x03\0a\0\x3e\xd8\x80\xdd");
+        assert_eq!(PascalString::<7, U16LE>::read_from(&mut &bytes[..], Layout::Compact, ByteOrder::LittleEndian)
+                       .unwrap(), string);
+
+        let string = PascalString::<40, U16BE>::from_str("señor 🦀, ¿cómo está? Bien, gracias.").unwrap();
+        let mut bytes = Vec::new();
+        string.write_to(&mut bytes, Layout::Record, ByteOrder::BigEndian).unwrap();
+        assert_eq!(bytes.len(), 2 + 2 * 40);
+        assert_eq!(&bytes[..6], b"\0\x24\0s\0e");
+        assert!(bytes[2 + 2 * string.len()..].iter().all(|&b| b == 0));
+        bytes.push(b'!');
+        let mut reader = &bytes[..];
+        assert_eq!(PascalString::<40, U16BE>::read_from(&mut reader, Layout::Record, ByteOrder::BigEndian).unwrap(),
+                   string);
+        assert_eq!(reader, b"!");
+
+        let err = PascalString::<1>::read_from(&mut &b"\x02a\0b\0"[..], Layout::Compact, ByteOrder::LittleEndian)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = PascalString::<7>::read_from(&mut &b"\x01\xd8\x3e"[..], Layout::Compact, ByteOrder::BigEndian)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = PascalString::<7>::read_from(&mut &b"\x01\0"[..], Layout::Compact, ByteOrder::BigEndian)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let string = PascalString::<3>::from_str("a🦀").unwrap();
+        let array: [u8; 7] = string.clone().to_array(ByteOrder::BigEndian);
+        assert_eq!(&array, b"\x03\0a\xd8\x3e\xdd\x80");
+        assert_eq!(PascalString::<3>::from_array(array, ByteOrder::BigEndian), Ok(string.clone()));
+        let array: [u8; 7] = string.clone().to_array(ByteOrder::LittleEndian);
+        assert_eq!(PascalString::<3>::from_array(array, ByteOrder::LittleEndian), Ok(string));
+
+        assert_eq!(PascalString::<3>::from_array(*b"\x04\0a\0b\0c", ByteOrder::BigEndian),
+                   Err(PascalStringCreateError::InputTooLong { required: 4, available: 3 }));
+        assert!(PascalString::<3>::from_array(*b"\x01\xd8\x3e\0\0\0\0", ByteOrder::BigEndian).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use core::cmp::Ordering;
+use core::char;
+use core::fmt::{self, Write};
+use core::hash::{Hash, Hasher};
+use core::iter::{DoubleEndedIterator, Iterator};
+use core::ops::{Index, Range, RangeFull, RangeFrom, RangeTo};
+use core::slice::Iter;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// A borrowed slice from a `PascalString`. Does not own its data.
+///
+/// The code units of a `PascalStr` are always valid UTF-16: every high surrogate is followed by a low
+/// surrogate, and there are no unpaired surrogates.
+#[repr(transparent)]
+pub struct PascalStr {
+    /// The UTF-16 code units, borrowed from the original `PascalString`
+    units: [u16]
+}
+
+impl PascalStr {
+    /// Borrow `units` as a `PascalStr`.
+    ///
+    /// Returns an `Err` if `units` contains an unpaired surrogate.
+    #[inline]
+    pub fn from_units(units: &[u16]) -> Result<&PascalStr, Utf16Error> {
+        validate(units)?;
+        Ok(unsafe { PascalStr::from_units_unchecked(units) })
+    }
+
+    /// Borrow `units` as a `PascalStr`, without checking that it is valid UTF-16.
+    #[inline]
+    pub(crate) unsafe fn from_units_unchecked(units: &[u16]) -> &PascalStr {
+        &*(units as *const [u16] as *const PascalStr)
+    }
+
+    /// Get a pointer to the first code unit of the string buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u16 {
+        self.units.as_ptr()
+    }
+
+    /// Get the UTF-16 code units of the `PascalStr`.
+    #[inline]
+    pub fn as_units(&self) -> &[u16] {
+        &self.units
+    }
+
+    /// Returns the number of UTF-16 code units used in the string.
+    ///
+    /// Note that this is not the same as the number of characters in the string, as characters outside the
+    /// basic multilingual plane take up two code units.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Returns true if the string has a length of 0
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Returns true if `index` is on a character boundary: it is the start or end of the string, or the code
+    /// unit at `index` is not the second half of a surrogate pair.
+    #[inline]
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        match self.units.get(index) {
+            Some(&unit) => !is_low_surrogate(unit),
+            None => index == self.len()
+        }
+    }
+
+    /// Decode the character which starts at the code unit `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds, or lies between the two halves of a surrogate pair.
+    #[inline]
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        if index < self.len() && self.is_char_boundary(index) {
+            self[index..].chars().next()
+        } else {
+            None
+        }
+    }
+
+    /// Get an iterator over the characters of the string.
+    #[inline]
+    pub fn chars(&self) -> Chars<'_> {
+        Chars(&self.units)
+    }
+
+    /// Get an iterator over the UTF-16 code units of the string.
+    #[inline]
+    pub fn units(&self) -> Iter<'_, u16> {
+        self.units.iter()
+    }
+
+    /// Slices the string between the code units `start` and `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds, or lies between the two halves of a surrogate pair.
+    #[inline]
+    fn slice(&self, start: usize, end: usize) -> &PascalStr {
+        assert!(start <= end && end <= self.len(), "index out of bounds");
+        assert!(self.is_char_boundary(start) && self.is_char_boundary(end),
+                "index is not on a character boundary");
+        unsafe { PascalStr::from_units_unchecked(&self.units[start..end]) }
+    }
+}
+
+impl fmt::Debug for PascalStr {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.write_char('"')?;
+        for ch in self.chars() {
+            for escaped in ch.escape_debug() {
+                fmtr.write_char(escaped)?;
+            }
+        }
+        fmtr.write_char('"')
+    }
+}
+
+impl fmt::Display for PascalStr {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.chars() {
+            fmtr.write_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for PascalStr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.units == other.units
+    }
+}
+
+impl Eq for PascalStr { }
+
+impl PartialEq<str> for PascalStr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl<'a> PartialEq<&'a str> for PascalStr {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl PartialOrd for PascalStr {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PascalStr {
+    /// Compares the strings by their characters, which agrees with the ordering of the same strings as `str`.
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.chars().cmp(other.chars())
+    }
+}
+
+impl Hash for PascalStr {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.units.hash(state);
+    }
+}
+
+impl Default for &PascalStr {
+    #[inline]
+    fn default() -> Self {
+        unsafe { PascalStr::from_units_unchecked(&[]) }
+    }
+}
+
+impl AsRef<[u16]> for PascalStr {
+    #[inline]
+    fn as_ref(&self) -> &[u16] {
+        &self.units
+    }
+}
+
+impl Index<RangeFull> for PascalStr {
+    type Output = PascalStr;
+
+    #[inline]
+    fn index(&self, _: RangeFull) -> &Self::Output {
+        self
+    }
+}
+
+impl Index<Range<usize>> for PascalStr {
+    type Output = PascalStr;
+
+    #[inline]
+    fn index(&self, index: Range<usize>) -> &Self::Output {
+        self.slice(index.start, index.end)
+    }
+}
+
+impl Index<RangeFrom<usize>> for PascalStr {
+    type Output = PascalStr;
+
+    #[inline]
+    fn index(&self, index: RangeFrom<usize>) -> &Self::Output {
+        self.slice(index.start, self.len())
+    }
+}
+
+impl Index<RangeTo<usize>> for PascalStr {
+    type Output = PascalStr;
+
+    #[inline]
+    fn index(&self, index: RangeTo<usize>) -> &Self::Output {
+        self.slice(0, index.end)
+    }
+}
+
+impl<'a> IntoIterator for &'a PascalStr {
+    type Item = char;
+    type IntoIter = Chars<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.chars()
+    }
+}
+
+/// An iterator over the characters of a `PascalStr`.
+#[derive(Clone, Debug)]
+pub struct Chars<'a>(&'a [u16]);
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&first, rest) = self.0.split_first()?;
+        if is_high_surrogate(first) {
+            self.0 = &rest[1..];
+            Some(combine_surrogates(first, rest[0]))
+        } else {
+            self.0 = rest;
+            Some(unsafe { char::from_u32_unchecked(first as u32) })
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len();
+        (len.div_ceil(2), Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Chars<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (&last, rest) = self.0.split_last()?;
+        if is_low_surrogate(last) {
+            let (&high, rest) = rest.split_last().unwrap();
+            self.0 = rest;
+            Some(combine_surrogates(high, last))
+        } else {
+            self.0 = rest;
+            Some(unsafe { char::from_u32_unchecked(last as u32) })
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn is_high_surrogate(unit: u16) -> bool {
+    unit & 0xFC00 == 0xD800
+}
+
+#[inline]
+pub(crate) fn is_low_surrogate(unit: u16) -> bool {
+    unit & 0xFC00 == 0xDC00
+}
+
+#[inline]
+fn combine_surrogates(high: u16, low: u16) -> char {
+    let code = 0x10000 + (((high as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+    unsafe { char::from_u32_unchecked(code) }
+}
+
+/// Checks that `units` contains no unpaired surrogates.
+pub(crate) fn validate(units: &[u16]) -> Result<(), Utf16Error> {
+    let mut i = 0;
+    while i < units.len() {
+        let unit = units[i];
+        if is_high_surrogate(unit) && i + 1 < units.len() && is_low_surrogate(units[i + 1]) {
+            i += 2;
+        } else if is_high_surrogate(unit) || is_low_surrogate(unit) {
+            return Err(Utf16Error { valid_up_to: i, unpaired_surrogate: unit });
+        } else {
+            i += 1;
+        }
+    }
+    Ok(())
+}
+
+/// The error returned when a sequence of code units is not valid UTF-16, because it contains an unpaired
+/// surrogate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf16Error {
+    valid_up_to: usize,
+    unpaired_surrogate: u16
+}
+
+impl Utf16Error {
+    /// Returns the index of the unpaired surrogate. All of the code units before it are valid UTF-16.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+
+    /// Returns the unpaired surrogate.
+    #[inline]
+    pub fn unpaired_surrogate(&self) -> u16 {
+        self.unpaired_surrogate
+    }
+}
+
+impl fmt::Display for Utf16Error {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "unpaired surrogate {:#06x} at index {}", self.unpaired_surrogate, self.valid_up_to)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for Utf16Error {
+    fn description(&self) -> &str {
+        "the input data is not valid utf16"
+    }
+}
@@ -0,0 +1,598 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use core::iter;
+use core::ops::Deref;
+use core::str::FromStr;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+use ::utf16::{PascalStr, Utf16Error};
+use ::utf16::pascal_str::{self, is_low_surrogate};
+use ::prefix::{LengthPrefix, U8};
+use ::utf8;
+#[cfg(feature = "std")]
+use ::Layout;
+use ::PASCAL_STRING_BUF_SIZE;
+
+/// An owned `PascalString`. This string type stores its data the stack, as UTF-16 code units.
+///
+/// The capacity `N` and the stored length are measured in code units, and `N` defaults to 255. It may not be
+/// larger than the maximum length which can be stored in the length prefix `L`. Characters outside the basic
+/// multilingual plane take up two code units, as a surrogate pair.
+///
+/// The string is always valid UTF-16, so it can be converted losslessly to and from a `utf8::PascalString`.
+pub struct PascalString<const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
+    /// The number of code units used in the string.
+    len: L::Len,
+    /// The internal code unit buffer, encoded in UTF-16.
+    units: [u16; N]
+}
+
+impl<const N: usize, L: LengthPrefix> PascalString<N, L> {
+    /// Creates a new, empty `PascalString`.
+    #[inline]
+    pub const fn new() -> Self {
+        let () = ::CapacityCheck::<L, N>::VALID;
+        PascalString {
+            len: L::EMPTY,
+            units: [0u16; N]
+        }
+    }
+
+    /// Creates a new `PascalString` by encoding `s` as UTF-16.
+    ///
+    /// Returns an `Err` if `s` needs more code units than the capacity of the string.
+    #[inline]
+    pub fn from_str<S: AsRef<str>>(s: S) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_str(s.as_ref())
+    }
+
+    fn _from_str(s: &str) -> Result<Self, PascalStringCreateError> {
+        let mut string = PascalString::new();
+        match string._try_push_str(s) {
+            Ok(()) => Ok(string),
+            Err(PascalStringAppendError::NoRoom { required, available }) => {
+                Err(PascalStringCreateError::InputTooLong { required: required, available: available })
+            }
+        }
+    }
+
+    /// Creates a new `PascalString` from UTF-16 code units.
+    ///
+    /// Returns an `Err` if `units` is longer than the capacity of the string, or contains an unpaired
+    /// surrogate.
+    #[inline]
+    pub fn from_units<U: AsRef<[u16]>>(units: U) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_units(units.as_ref())
+    }
+
+    fn _from_units(units: &[u16]) -> Result<Self, PascalStringCreateError> {
+        let len = units.len();
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
+        }
+        pascal_str::validate(units)?;
+        let mut string = PascalString::new();
+        string.units[..len].copy_from_slice(units);
+        string.set_len(len);
+        Ok(string)
+    }
+
+    /// Creates a new `PascalString` from the contents of a `utf8::PascalStr`, or any other string.
+    ///
+    /// This is the same as `from_str()`. A string never needs more UTF-16 code units than utf8 bytes, so
+    /// converting a `utf8::PascalString` into a `utf16::PascalString` of the same capacity always succeeds.
+    #[inline]
+    pub fn from_utf8<S: AsRef<str>>(s: S) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_str(s.as_ref())
+    }
+
+    /// Converts this string into a `utf8::PascalString`.
+    ///
+    /// Returns an `Err` if the utf8 encoding of the string is longer than the capacity `M`. A character may
+    /// take up to three utf8 bytes for each UTF-16 code unit, so this can fail even if `M` is equal to `N`.
+    pub fn to_utf8<const M: usize>(&self) -> Result<utf8::PascalString<M, L>, utf8::PascalStringCreateError> {
+        let mut string = utf8::PascalString::new();
+        let mut required = 0;
+        for ch in self.chars() {
+            required += ch.len_utf8();
+            if required <= M {
+                string.push(ch);
+            }
+        }
+        if required > M {
+            return Err(utf8::PascalStringCreateError::InputTooLong { required: required, available: M });
+        }
+        Ok(string)
+    }
+
+    /// Push a character onto the end of the string's internal buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no room to store the `char`.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.try_push(ch).unwrap()
+    }
+
+    /// Attempt to push a `char` onto the end of this string's internal buffer.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the operation succeeded, otherwise an error is returned.
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        let ch_len = ch.len_utf16();
+        if len + ch_len > N {
+            return Err(PascalStringAppendError::NoRoom { required: ch_len, available: N - len });
+        }
+        let _ = ch.encode_utf16(&mut self.units[len..]);
+        self.set_len(len + ch_len);
+        Ok(())
+    }
+
+    /// Push a string onto the end of this string's internal buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no room to store the `str`.
+    #[inline]
+    pub fn push_str<S: AsRef<str>>(&mut self, s: S) {
+        self.try_push_str(s).unwrap()
+    }
+
+    /// Attempt to push a string onto the end of this string's internal buffer.
+    ///
+    /// The string is left unchanged if an error is returned.
+    #[inline]
+    pub fn try_push_str<S: AsRef<str>>(&mut self, s: S) -> Result<(), PascalStringAppendError> {
+        self._try_push_str(s.as_ref())
+    }
+
+    fn _try_push_str(&mut self, s: &str) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        let required = s.encode_utf16().count();
+        if len + required > N {
+            return Err(PascalStringAppendError::NoRoom { required: required, available: N - len });
+        }
+        for (unit, slot) in s.encode_utf16().zip(&mut self.units[len..]) {
+            *slot = unit;
+        }
+        self.set_len(len + required);
+        Ok(())
+    }
+
+    /// Removes the last character from the string buffer and returns it.
+    ///
+    /// Returns `None` if this `PascalString` is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let len = self.len() - ch.len_utf16();
+        self.set_len(len);
+        Some(ch)
+    }
+
+    /// Truncates this String, removing all contents.
+    ///
+    /// Does not zero the values of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set_len(0);
+    }
+
+    /// Shortens this string to `new_len` code units. Has no effect if `new_len` is not less than the length of
+    /// the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` lies between the two halves of a surrogate pair.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(!is_low_surrogate(self.units[new_len]), "new_len is not on a character boundary");
+            self.set_len(new_len);
+        }
+    }
+
+    /// Create a new `PascalString` from `reader`, by reading a length prefix followed by the code units of the
+    /// string in the byte order `order`, laid out as described by `layout`. The length prefix counts code units,
+    /// and a `Layout::Record` string is followed by `N` code units of data.
+    ///
+    /// If the length prefix is larger than the capacity of the string, or the code units contain an unpaired
+    /// surrogate, then an error of kind `io::ErrorKind::InvalidData` is returned, which wraps a
+    /// `PascalStringCreateError`.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R, layout: Layout, order: ByteOrder) -> io::Result<Self> {
+        let len = ::read_prefix::<L, R>(reader)?;
+        if len > N {
+            let error = PascalStringCreateError::InputTooLong { required: len, available: N };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        let mut string = PascalString::new();
+        let mut buf = [0u8; 64];
+        for units in string.units[..len].chunks_mut(buf.len() / 2) {
+            let bytes = &mut buf[..2 * units.len()];
+            reader.read_exact(bytes)?;
+            for (unit, pair) in units.iter_mut().zip(bytes.chunks(2)) {
+                *unit = order.read_unit([pair[0], pair[1]]);
+            }
+        }
+        if let Err(e) = pascal_str::validate(&string.units[..len]) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, PascalStringCreateError::from(e)));
+        }
+        string.set_len(len);
+        if layout == Layout::Record {
+            ::skip_bytes(reader, 2 * (N - len))?;
+        }
+        Ok(string)
+    }
+
+    /// Write this `PascalString` to `writer`, as a length prefix followed by the code units of the string in the
+    /// byte order `order`, laid out as described by `layout`.
+    ///
+    /// Unlike `to_array()`, stale code units past the end of the string are never written.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, writer: &mut W, layout: Layout, order: ByteOrder) -> io::Result<()> {
+        ::write_prefix::<L, W>(writer, self.len())?;
+        let padding = if layout == Layout::Record { N - self.len() } else { 0 };
+        let mut buf = [0u8; 64];
+        let mut used = 0;
+        for unit in self.units().cloned().chain(iter::repeat_n(0, padding)) {
+            buf[used..used + 2].copy_from_slice(&order.write_unit(unit));
+            used += 2;
+            if used == buf.len() {
+                writer.write_all(&buf)?;
+                used = 0;
+            }
+        }
+        writer.write_all(&buf[..used])
+    }
+
+    /// Create a new `PascalString` from a `[u8; M]`, where the first bytes store the length prefix, and the
+    /// rest store `N` code units in the byte order `order`. `M` must be equal to `2 * N + L::WIDTH`.
+    ///
+    /// Returns an `Err` if the length prefix is larger than the capacity of the string, or if the code units
+    /// contain an unpaired surrogate.
+    ///
+    /// Using an `M` which is not equal to `2 * N + L::WIDTH` is a compile time error.
+    pub fn from_array<const M: usize>(array: [u8; M], order: ByteOrder) -> Result<Self, PascalStringCreateError> {
+        let () = ::UnitArrayLenCheck::<L, N, M>::VALID;
+        let len = L::to_usize(L::read_len(&array));
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
+        }
+        let mut string = PascalString::new();
+        for (unit, pair) in string.units.iter_mut().zip(array[L::WIDTH..].chunks(2)) {
+            *unit = order.read_unit([pair[0], pair[1]]);
+        }
+        pascal_str::validate(&string.units[..len])?;
+        string.set_len(len);
+        Ok(string)
+    }
+
+    /// Consumes this `PascalString`, and returns its inner state as a `[u8; M]`, where the first bytes store the
+    /// length prefix, and the rest store the code units of the string in the byte order `order`. `M` must be
+    /// equal to `2 * N + L::WIDTH`.
+    ///
+    /// Using an `M` which is not equal to `2 * N + L::WIDTH` is a compile time error.
+    pub fn to_array<const M: usize>(self, order: ByteOrder) -> [u8; M] {
+        let () = ::UnitArrayLenCheck::<L, N, M>::VALID;
+        let mut array = [0u8; M];
+        L::write_len(self.len, &mut array);
+        for (pair, &unit) in array[L::WIDTH..].chunks_mut(2).zip(self.units.iter()) {
+            pair.copy_from_slice(&order.write_unit(unit));
+        }
+        array
+    }
+
+    /// Returns the maximum number of code units which this `PascalString` can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the number of code units used by the string is equal to its capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Sets the number of code units used by the string, without touching the contents of the buffer.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+        self.len = L::from_usize(len);
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> fmt::Debug for PascalString<N, L> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("PascalString")
+            .field("len", &self.len)
+            .field("units", &self.deref())
+            .finish()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> fmt::Display for PascalString<N, L> {
+    #[inline]
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.deref(), fmtr)
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> fmt::Write for PascalString<N, L> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Default for PascalString<N, L> {
+    #[inline]
+    fn default() -> Self {
+        PascalString::new()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Clone for PascalString<N, L> {
+    #[inline]
+    fn clone(&self) -> Self {
+        PascalString {
+            len: self.len,
+            units: self.units
+        }
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Hash for PascalString<N, L> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state);
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> PartialEq for PascalString<N, L> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Eq for PascalString<N, L> { }
+
+impl<const N: usize, L: LengthPrefix> PartialEq<str> for PascalString<N, L> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.deref() == other
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> PartialEq<&'a str> for PascalString<N, L> {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.deref() == *other
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> PartialOrd for PascalString<N, L> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Ord for PascalString<N, L> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Deref for PascalString<N, L> {
+    type Target = PascalStr;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { PascalStr::from_units_unchecked(&self.units[..L::to_usize(self.len)]) }
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> AsRef<PascalStr> for PascalString<N, L> {
+    #[inline]
+    fn as_ref(&self) -> &PascalStr {
+        self.deref()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> AsRef<[u16]> for PascalString<N, L> {
+    #[inline]
+    fn as_ref(&self) -> &[u16] {
+        self.as_units()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Borrow<PascalStr> for PascalString<N, L> {
+    #[inline]
+    fn borrow(&self) -> &PascalStr {
+        self.deref()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> From<utf8::PascalString<N, L>> for PascalString<N, L> {
+    /// Converts a `utf8::PascalString` into a `utf16::PascalString` with the same capacity. This cannot fail,
+    /// as a string never needs more UTF-16 code units than utf8 bytes.
+    #[inline]
+    fn from(string: utf8::PascalString<N, L>) -> Self {
+        PascalString::_from_str(string.as_str()).unwrap()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> TryFrom<PascalString<N, L>> for utf8::PascalString<N, L> {
+    type Error = utf8::PascalStringCreateError;
+
+    /// Converts a `utf16::PascalString` into a `utf8::PascalString` with the same capacity.
+    ///
+    /// Returns an `Err` if the string needs more utf8 bytes than the capacity of the string.
+    #[inline]
+    fn try_from(string: PascalString<N, L>) -> Result<Self, Self::Error> {
+        string.to_utf8()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for String {
+    #[inline]
+    fn from(string: PascalString<N, L>) -> Self {
+        string.chars().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for Vec<u16> {
+    #[inline]
+    fn from(string: PascalString<N, L>) -> Self {
+        string.as_units().to_vec()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> FromStr for PascalString<N, L> {
+    type Err = PascalStringCreateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PascalString::_from_str(s)
+    }
+}
+
+/// The order of the two bytes of each code unit, when a `PascalString` is converted to or from bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// The most significant byte comes first, as in UTF-16BE.
+    BigEndian,
+    /// The least significant byte comes first, as in UTF-16LE and COM `BSTR`s.
+    LittleEndian
+}
+
+impl ByteOrder {
+    #[inline]
+    fn read_unit(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes)
+        }
+    }
+
+    #[inline]
+    fn write_unit(self, unit: u16) -> [u8; 2] {
+        match self {
+            ByteOrder::BigEndian => unit.to_be_bytes(),
+            ByteOrder::LittleEndian => unit.to_le_bytes()
+        }
+    }
+}
+
+/// Indicates the range of errors which can occur from creating a new `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringCreateError {
+    /// The data provided to the constructor was larger than the `PascalString` could store.
+    InputTooLong {
+        /// The number of code units needed to store the data.
+        required: usize,
+        /// The capacity of the string.
+        available: usize
+    },
+    /// The code units provided contain an unpaired surrogate.
+    NotValidUtf16(Utf16Error)
+}
+
+impl PascalStringCreateError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringCreateError::InputTooLong { .. } => "the input data is longer than what a PascalString can store",
+            PascalStringCreateError::NotValidUtf16(_) => "the input data is not valid utf16"
+        }
+    }
+}
+
+impl fmt::Display for PascalStringCreateError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringCreateError::InputTooLong { required, available } => {
+                write!(fmtr, "{} ({} code units required, {} available)", self.message(), required, available)
+            }
+            PascalStringCreateError::NotValidUtf16(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringCreateError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PascalStringCreateError::NotValidUtf16(ref e) = *self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Utf16Error> for PascalStringCreateError {
+    #[inline]
+    fn from(e: Utf16Error) -> Self {
+        PascalStringCreateError::NotValidUtf16(e)
+    }
+}
+
+/// Indicates the range of errors which can occur from appending string data to a `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringAppendError {
+    /// There is no room to store the appended data.
+    NoRoom {
+        /// The number of code units needed to store the appended data.
+        required: usize,
+        /// The number of code units which were left in the string.
+        available: usize
+    }
+}
+
+impl PascalStringAppendError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringAppendError::NoRoom { .. } => "there is no room for the string to be appended"
+        }
+    }
+}
+
+impl fmt::Display for PascalStringAppendError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringAppendError::NoRoom { required, available } => {
+                write!(fmtr, "{} ({} code units required, {} available)", self.message(), required, available)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringAppendError {
+    #[inline]
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
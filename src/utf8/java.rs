@@ -0,0 +1,189 @@
+use core::char;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+use ::utf8::{PascalStr, PascalString};
+use ::prefix::{LengthPrefix, U16BE};
+
+/// Returns the number of bytes needed to store `s` in Java's modified utf8, not counting the length prefix.
+pub fn java_utf_len(s: &PascalStr) -> usize {
+    s.chars().map(java_char_len).sum()
+}
+
+#[inline]
+fn java_char_len(ch: char) -> usize {
+    match ch as u32 {
+        0x01..=0x7F => 1,
+        0x00 | 0x80..=0x7FF => 2,
+        0x800..=0xFFFF => 3,
+        _ => 6
+    }
+}
+
+/// Encode `s` into `buf` in the format of Java's `DataOutput.writeUTF`: a big endian `u16` length prefix, followed
+/// by the string in modified utf8. In modified utf8, the null character is encoded as `C0 80`, and characters
+/// outside the basic multilingual plane are encoded as a surrogate pair of three byte sequences.
+///
+/// Returns the number of bytes written to `buf`.
+///
+/// Returns an `Err` if the encoded string is longer than the 65535 bytes which the length prefix can describe, or
+/// does not fit into `buf`.
+pub fn encode_java_utf(s: &PascalStr, buf: &mut [u8]) -> Result<usize, JavaUtfError> {
+    let len = java_utf_len(s);
+    if len > U16BE::MAX_LEN {
+        return Err(JavaUtfError::NoRoom { required: len, available: U16BE::MAX_LEN });
+    }
+    if buf.len() < U16BE::WIDTH + len {
+        return Err(JavaUtfError::NoRoom { required: U16BE::WIDTH + len, available: buf.len() });
+    }
+    U16BE::write_len(U16BE::from_usize(len), buf);
+    let mut i = U16BE::WIDTH;
+    let mut units = [0u16; 2];
+    for ch in s.chars() {
+        match java_char_len(ch) {
+            1 => {
+                buf[i] = ch as u8;
+                i += 1;
+            }
+            2 => {
+                let code = ch as u32;
+                buf[i] = 0xC0 | (code >> 6) as u8;
+                buf[i + 1] = 0x80 | (code & 0x3F) as u8;
+                i += 2;
+            }
+            _ => {
+                for &unit in ch.encode_utf16(&mut units).iter() {
+                    buf[i] = 0xE0 | (unit >> 12) as u8;
+                    buf[i + 1] = 0x80 | ((unit >> 6) & 0x3F) as u8;
+                    buf[i + 2] = 0x80 | (unit & 0x3F) as u8;
+                    i += 3;
+                }
+            }
+        }
+    }
+    Ok(i)
+}
+
+/// Decode a string written by Java's `DataOutput.writeUTF` from the start of `bytes`. See `encode_java_utf()` for
+/// the format.
+///
+/// On success, the `PascalString` is returned along with the remainder of `bytes` which follows the end of the
+/// string.
+///
+/// Returns an `Err` if `bytes` is shorter than its length prefix says, if the string contains a malformed
+/// sequence or an unpaired surrogate, or if the decoded string is longer than the capacity `N`.
+pub fn decode_java_utf<const N: usize, L: LengthPrefix>(bytes: &[u8])
+                                                        -> Result<(PascalString<N, L>, &[u8]), JavaUtfError> {
+    if bytes.len() < U16BE::WIDTH {
+        return Err(JavaUtfError::Truncated { required: U16BE::WIDTH, available: bytes.len() });
+    }
+    let len = U16BE::to_usize(U16BE::read_len(bytes));
+    if bytes.len() < U16BE::WIDTH + len {
+        return Err(JavaUtfError::Truncated { required: U16BE::WIDTH + len, available: bytes.len() });
+    }
+    let (data, rest) = bytes[U16BE::WIDTH..].split_at(len);
+    let malformed = |index: usize| JavaUtfError::Malformed { offset: U16BE::WIDTH + index };
+
+    let mut string = PascalString::new();
+    let mut required = 0;
+    let mut i = 0;
+    while i < data.len() {
+        let (unit, unit_len) = decode_unit(&data[i..]).ok_or_else(|| malformed(i))?;
+        let (ch, ch_len) = match unit {
+            0xD800..=0xDBFF => {
+                let (low, low_len) = decode_unit(&data[i + unit_len..]).ok_or_else(|| malformed(i))?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(malformed(i));
+                }
+                let code = 0x10000 + (((unit as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+                (char::from_u32(code).unwrap(), unit_len + low_len)
+            }
+            0xDC00..=0xDFFF => return Err(malformed(i)),
+            _ => (char::from_u32(unit as u32).unwrap(), unit_len)
+        };
+        required += ch.len_utf8();
+        if required <= N {
+            string.push(ch);
+        }
+        i += ch_len;
+    }
+    if required > N {
+        return Err(JavaUtfError::NoRoom { required: required, available: N });
+    }
+    Ok((string, rest))
+}
+
+/// Decode a single UTF-16 code unit from the start of `bytes`, as Java's `DataInput.readUTF` does. Returns the code
+/// unit and the number of bytes it used, or `None` if the sequence is malformed.
+fn decode_unit(bytes: &[u8]) -> Option<(u16, usize)> {
+    let continuation = |index: usize| match bytes.get(index) {
+        Some(&byte) if byte & 0xC0 == 0x80 => Some((byte & 0x3F) as u16),
+        _ => None
+    };
+    let first = match bytes.first() {
+        Some(&byte) => byte as u16,
+        None => return None
+    };
+    match first {
+        0x00..=0x7F => Some((first, 1)),
+        0xC0..=0xDF => continuation(1).map(|second| (((first & 0x1F) << 6) | second, 2)),
+        0xE0..=0xEF => match (continuation(1), continuation(2)) {
+            (Some(second), Some(third)) => Some((((first & 0x0F) << 12) | (second << 6) | third, 3)),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+/// Indicates the range of errors which can occur when encoding or decoding Java's modified utf8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JavaUtfError {
+    /// The input is shorter than the length prefix, or the string data which follows it.
+    Truncated {
+        /// The number of bytes needed to hold the length prefix, or the length prefix and the string data.
+        required: usize,
+        /// The length of the input.
+        available: usize
+    },
+    /// The input contains a malformed sequence or an unpaired surrogate.
+    Malformed {
+        /// The byte offset of the sequence in the input, counting the length prefix.
+        offset: usize
+    },
+    /// The output is too small to hold the converted string.
+    NoRoom {
+        /// The number of bytes needed to store the converted string.
+        required: usize,
+        /// The capacity of the output.
+        available: usize
+    }
+}
+
+impl JavaUtfError {
+    fn message(&self) -> &'static str {
+        match *self {
+            JavaUtfError::Truncated { .. } => "the input is shorter than the length prefix of the string",
+            JavaUtfError::Malformed { .. } => "the input is not valid modified utf8",
+            JavaUtfError::NoRoom { .. } => "there is no room for the converted string"
+        }
+    }
+}
+
+impl fmt::Display for JavaUtfError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JavaUtfError::Truncated { required, available } | JavaUtfError::NoRoom { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            JavaUtfError::Malformed { offset } => write!(fmtr, "{} at offset {}", self.message(), offset)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for JavaUtfError {
+    #[inline]
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
@@ -1,35 +1,106 @@
-#![allow(missing_docs, unused_variables)]
-
+mod java;
+mod packed;
 mod pascal_str;
 mod pascal_string;
+mod pattern;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod split;
+
+pub use self::java::{decode_java_utf, encode_java_utf, java_utf_len, JavaUtfError};
+pub use self::packed::{PackedPascalStrings, PackedPascalStringsWriter};
+pub use self::pascal_str::{Chars, Bytes, Lines, PascalStr, PrefixedBytesError};
+pub use self::pascal_string::{Drain, IntoChars, PascalString, PascalStringAppendError, PascalStringCreateError};
+pub use self::pattern::Pattern;
+pub use self::split::{RSplit, Split, SplitN, SplitTerminator, SplitWhitespace};
+pub use self::pascal_string::{PascalString15, PascalString31, PascalString63, PascalString255};
+pub use ::ascii::InteriorNullError;
+
+/// Create a `utf8::PascalString` from a string literal at compile time.
+///
+/// The capacity of the string is taken from the type the macro is used as, so this can be used to fill
+/// `const` and `static` tables. Compilation fails if the literal is longer than the capacity of the
+/// string.
+///
+/// ```
+/// use pascal_string::utf8::{pstr, PascalString15};
+///
+/// static COMMANDS: [PascalString15; 2] = [pstr!("ouvrir"), pstr!("fermé")];
+/// assert_eq!(COMMANDS[1], "fermé");
+/// ```
+///
+/// ```compile_fail
+/// use pascal_string::utf8::{pstr, PascalString15};
+///
+/// static BAD: PascalString15 = pstr!("much too long for this");
+/// ```
+#[doc(inline)]
+pub use __utf8_pstr as pstr;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __utf8_pstr {
+    ($s:expr) => {
+        const { $crate::utf8::PascalString::from_literal($s) }
+    }
+}
 
-pub use self::pascal_str::{Chars, Bytes, Lines, PascalStr};
-pub use self::pascal_string::{PascalString, PascalStringAppendError, PascalStringCreateError};
+/// Create a `utf8::PascalString` from formatted text, like `format!`, without allocating.
+///
+/// Returns a `Result<PascalString, PascalStringAppendError>`, which is an `Err` if the formatted text is
+/// longer than the capacity of the string. If the arguments are prefixed with `truncate;`, the text is
+/// truncated to the capacity of the string instead.
+///
+/// ```
+/// use pascal_string::utf8::{pformat, PascalString15, PascalStringAppendError};
+///
+/// let string: PascalString15 = pformat!("{}-{}", "señor", 42).unwrap();
+/// assert_eq!(string, "señor-42");
+/// assert_eq!(pformat!("{:>20}", 42) as Result<PascalString15, _>,
+///            Err(PascalStringAppendError::NoRoom { required: 20, available: 15 }));
+///
+/// let truncated: PascalString15 = pformat!(truncate; "{:>20}", 42).unwrap();
+/// assert_eq!(truncated.len(), 15);
+/// ```
+#[doc(inline)]
+pub use __utf8_pformat as pformat;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __utf8_pformat {
+    (truncate; $($arg:tt)*) => {
+        $crate::utf8::PascalString::format_truncated(format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => {
+        $crate::utf8::PascalString::format(format_args!($($arg)*))
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "alloc")]
     use std::borrow::Cow;
+    #[cfg(feature = "alloc")]
     use std::ffi::{CStr, CString};
     use std::iter::IntoIterator;
+    use std::prelude::v1::*;
 
     #[test]
     fn test_string_creation() {
         let test = "Hello, my world!".to_owned();
-        let test_pascal = PascalString::from_str(&test).unwrap();
+        let test_pascal: PascalString = PascalString::from_str(&test).unwrap();
         assert_eq!(&test, test_pascal.as_str());
 
         let too_many_bytes = [12u8; 256];
         let too_long_a_string = String::from_utf8_lossy(&too_many_bytes);
-        assert!(match PascalString::from_str(&too_long_a_string) {
-            Err(PascalStringCreateError::InputTooLong) => true,
-            _ => false
-        });
+        assert!(matches!(PascalString255::from_str(&too_long_a_string),
+                         Err(PascalStringCreateError::InputTooLong { .. })));
     }
 
     #[test]
     fn test_character_append() {
-        let mut string = PascalString::new();
+        let mut string: PascalString = PascalString::new();
         assert!(string.try_push('h').is_ok());
         string.push('e');
         string.push(76u8 as char);
@@ -43,9 +114,555 @@ mod tests {
 
     #[test]
     fn test_string_append() {
-        let mut string = PascalString::new();
+        let mut string: PascalString = PascalString::new();
         string.push_str("Hola, ");
         string.push_str("señor!");
         assert_eq!(string, "Hola, señor!");
     }
+
+    #[test]
+    fn test_string_indexing_and_char_iteration() {
+        {
+            let string: PascalString = PascalString::from_str("qü").unwrap();
+            assert_eq!(&string[0u8..1u8], "q");
+            assert_eq!(&string[1..], "ü");
+            assert_eq!(&string[..], "qü");
+        }
+
+        {
+            let string2: PascalString = PascalString::from_str("WÄSD").unwrap();
+            {
+                let mut chars_iter = string2.chars();
+                assert_eq!(chars_iter.next(), Some('W'));
+                assert_eq!(chars_iter.next(), Some('Ä'));
+                assert_eq!(chars_iter.next(), Some('S'));
+                assert_eq!(chars_iter.next(), Some('D'));
+                assert_eq!(chars_iter.next(), None);
+            }
+
+            let mut into_chars = string2.into_iter();
+            assert_eq!(into_chars.next(), Some('W'));
+            assert_eq!(into_chars.next(), Some('Ä'));
+            assert_eq!(into_chars.next(), Some('S'));
+            assert_eq!(into_chars.next(), Some('D'));
+            assert_eq!(into_chars.next(), None);
+        }
+    }
+
+    #[test]
+    fn test_lines_iteration() {
+        let string = "hello\n,\nmy\ndarling".to_string();
+        let pstring: PascalString = PascalString::from_str(&string).unwrap();
+        assert_eq!(pstring.lines().count(), 4);
+        for (s0, s1) in pstring.lines().zip(string.split_whitespace()) {
+            assert_eq!(s0, s1);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_as_cstr() {
+        {
+            let msg = "I am your favourite cookie monster >:-)\0";
+            let pstr: PascalString = PascalString::from_str(msg).unwrap();
+            let cstr = CStr::from_bytes_with_nul(msg.as_bytes()).unwrap();
+            let pstr_as_cstr = pstr.as_cstr().unwrap();
+            assert!(matches!(pstr_as_cstr, Cow::Borrowed(_)));
+            assert_eq!(&*pstr_as_cstr, cstr);
+        }
+
+        {
+            let string_oversized: String = "l".repeat(255);
+            let pstr_oversized: PascalString = PascalString::from_str(&string_oversized).unwrap();
+            let cstr_from_pstr_oversized = pstr_oversized.as_cstr().unwrap();
+            let cstr_from_string_oversized = CString::new(string_oversized).unwrap();
+            assert!(matches!(cstr_from_pstr_oversized, Cow::Owned(_)));
+            assert_eq!(cstr_from_pstr_oversized.into_owned(), cstr_from_string_oversized);
+        }
+
+        {
+            let mut pstr: PascalString = PascalString::from_str("señor, señor").unwrap();
+            let _ = pstr.pop();
+            assert_eq!(&*pstr.as_cstr().unwrap(), CStr::from_bytes_with_nul("señor, seño\0".as_bytes()).unwrap());
+        }
+
+        {
+            let has_interior_null = "lol\0hi";
+            let pstr: PascalString = PascalString::from_str(has_interior_null).unwrap();
+            let err = pstr.as_cstr().unwrap_err();
+            assert_eq!(err.interior_null_index(), 3);
+        }
+    }
+
+    #[test]
+    fn test_character_removal_and_insertion() {
+        let mut string: PascalString = PascalString::from_str("señor!").unwrap();
+        assert_eq!(string.pop(), Some('!'));
+        assert_eq!(string.remove(2), 'ñ');
+        assert_eq!(string, "seor");
+        string.insert('ñ', 2);
+        string.insert('¡', 0);
+        string.insert('!', 8);
+        assert_eq!(string, "¡señor!");
+        string.clear();
+        assert!(string.is_empty());
+        assert_eq!(string.pop(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_off_char_boundary() {
+        let mut string: PascalString = PascalString::from_str("señor").unwrap();
+        let _ = string.remove(3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_conversions() {
+        let string: PascalString = "¿Qué?".parse().unwrap();
+        let owned: String = string.clone().into();
+        assert_eq!(owned, "¿Qué?");
+        let bytes: Vec<u8> = string.clone().into();
+        assert_eq!(bytes, "¿Qué?".as_bytes());
+
+        let collected: PascalString15 = "cañón".chars().chain("!".chars()).collect();
+        assert_eq!(collected, "cañón!");
+        let truncated: PascalString15 = ::std::iter::repeat_n('ü', 10).collect();
+        assert_eq!(truncated.len(), 14);
+
+        assert!(matches!(PascalString255::from_utf8([0x66, 0x6f, 0xff]),
+                         Err(PascalStringCreateError::NotValidUtf8(e)) if e.valid_up_to() == 2));
+        let mut array = [0u8; 15];
+        array[..3].copy_from_slice(b"abc");
+        assert_eq!(PascalString15::from_fixed_utf8_array(3, array).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_custom_capacity() {
+        let mut string = PascalString15::new();
+        assert_eq!(string.capacity(), 15);
+        assert_eq!(::std::mem::size_of::<PascalString15>(), 16);
+        string.push_str("señor, señor");
+        assert!(!string.is_full());
+        assert!(string.try_push('ñ').is_err());
+        string.push('!');
+        assert!(string.is_full());
+        assert!(matches!(PascalString15::from_str("señor, señor!!!"),
+                         Err(PascalStringCreateError::InputTooLong { .. })));
+        assert_eq!(string, "señor, señor!");
+    }
+
+    #[test]
+    fn test_wide_length_prefix() {
+        use prefix::U32BE;
+
+        let mut string = PascalString::<8, U32BE>::new();
+        string.push_str("señor");
+        assert!(string.try_push_str("!!!").is_err());
+        string.push_str("!!");
+        assert!(string.is_full());
+
+        let array: [u8; 12] = string.to_array();
+        assert_eq!(&array[..4], &[0, 0, 0, 8]);
+        assert_eq!(&array[4..], "señor!!".as_bytes());
+    }
+
+    #[test]
+    fn test_wide_string_past_255() {
+        use prefix::U16LE;
+
+        let mut string = PascalString::<400, U16LE>::new();
+        for _ in 0..30 {
+            string.push_str("abcdefghij");
+        }
+        string.insert('ñ', 299);
+        assert_eq!(string.len(), 302);
+        assert_eq!(string.remove(299), 'ñ');
+        assert_eq!(string.remove(299), 'j');
+
+        #[cfg(feature = "alloc")]
+        {
+            let owned = (*string).to_owned();
+            assert_eq!(owned.len(), 299);
+            assert_eq!(owned, string.as_str());
+        }
+        #[cfg(feature = "std")]
+        #[allow(deprecated)]
+        {
+            use std::ascii::AsciiExt;
+            let upper = AsciiExt::to_ascii_uppercase(&*string);
+            assert_eq!(upper.len(), 299);
+            assert!(upper.starts_with("ABCDEFGHIJ"));
+        }
+    }
+
+    #[test]
+    fn test_from_prefixed_bytes() {
+        use prefix::U32LE;
+
+        let buf = b"\x06se\xc3\xb1or\x01!";
+        let (senor, rest) = PascalStr::from_prefixed_bytes(buf).unwrap();
+        assert_eq!(senor, "señor");
+        assert_eq!(senor.as_ptr(), buf[1..].as_ptr());
+        let (bang, rest) = PascalStr::from_prefixed_bytes(rest).unwrap();
+        assert_eq!(bang, "!");
+
+        assert_eq!(PascalStr::from_prefixed_bytes(rest),
+                   Err(PrefixedBytesError::Truncated { required: 1, available: 0 }));
+        assert_eq!(PascalStr::from_prefixed_bytes(b"\x06short"),
+                   Err(PrefixedBytesError::Truncated { required: 7, available: 6 }));
+        assert!(matches!(PascalStr::from_prefixed_bytes(b"\x02\xc3a"), Err(PrefixedBytesError::NotValidUtf8(_))));
+
+        let (wide, rest) = PascalStr::from_prefixed_bytes_with::<U32LE>(b"\x02\x00\x00\x00\xc3\xb1").unwrap();
+        assert_eq!(wide, "ñ");
+        assert!(rest.is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_packed_strings() {
+        let buf = b"\x06se\xc3\xb1or\x01!\x00rest";
+        let mut strings = PackedPascalStrings::new(buf).until_empty();
+        assert_eq!(strings.next().unwrap().unwrap(), "señor");
+        assert_eq!(strings.next().unwrap().unwrap(), "!");
+        assert_eq!(strings.next(), None);
+        assert_eq!(strings.remainder(), b"rest");
+
+        let counted: Vec<_> = PackedPascalStrings::new(buf).with_count(1).collect();
+        assert_eq!(counted.len(), 1);
+
+        let mut vec = Vec::new();
+        {
+            let mut writer = PackedPascalStringsWriter::new(&mut vec);
+            writer.push(PascalString15::from_str("señor").unwrap()).unwrap();
+            writer.push(PascalString15::from_str("!").unwrap()).unwrap();
+            writer.push_terminator().unwrap();
+        }
+        assert_eq!(&vec[..], &buf[..10]);
+
+        let mut small = [0u8; 4];
+        let mut writer = PackedPascalStringsWriter::new(&mut small[..]);
+        assert_eq!(writer.push(PascalString15::from_str("señor").unwrap()),
+                   Err(PascalStringAppendError::NoRoom { required: 7, available: 4 }));
+        assert_eq!(writer.written(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_and_write() {
+        use std::io::{Cursor, ErrorKind};
+        use Layout;
+
+        let string = PascalString15::from_str("señor").unwrap();
+        let mut compact = Vec::new();
+        string.write_to(&mut compact, Layout::Compact).unwrap();
+        assert_eq!(&compact[..], b"\x06se\xc3\xb1or");
+        let mut record = Vec::new();
+        string.write_to(&mut record, Layout::Record).unwrap();
+        assert_eq!(record.len(), 16);
+        assert!(record[7..].iter().all(|&b| b == 0));
+
+        let mut reader = Cursor::new(record);
+        assert_eq!(PascalString15::read_from(&mut reader, Layout::Record).unwrap(), "señor");
+        assert_eq!(reader.position(), 16);
+        assert_eq!(PascalString15::read_from(&mut &compact[..], Layout::Compact).unwrap(), "señor");
+
+        let err = PascalString15::read_from(&mut &compact[..5], Layout::Compact).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = PascalString15::read_from(&mut &b"\x10"[..], Layout::Compact).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.get_ref().unwrap().downcast_ref::<PascalStringCreateError>(),
+                   Some(&PascalStringCreateError::InputTooLong { required: 16, available: 15 }));
+        let err = PascalString15::read_from(&mut &b"\x01\xc3"[..], Layout::Compact).unwrap_err();
+        assert!(matches!(err.get_ref().unwrap().downcast_ref::<PascalStringCreateError>(),
+                         Some(&PascalStringCreateError::NotValidUtf8(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Compact, Configure, Readable, Token};
+
+        let string = PascalString15::from_str("señor").unwrap();
+        assert_tokens(&string.clone().readable(), &[Token::Str("señor")]);
+        assert_tokens(&string.compact(), &[Token::Bytes("señor".as_bytes())]);
+        assert_de_tokens_error::<Readable<PascalString15>>(&[Token::Str("señor, señor!!!")],
+                                                 &PascalString15::from_str("señor, señor!!!").unwrap_err().to_string());
+        assert_de_tokens_error::<Compact<PascalString15>>(&[Token::Bytes(b"se\xc3or")],
+                                                 &PascalString15::from_utf8(b"se\xc3or").unwrap_err().to_string());
+
+        let mut tokens = vec![Token::Seq { len: Some(20) }];
+        tokens.extend((0..20).map(|_| Token::U8(b'a')));
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens_error::<Readable<PascalString15>>(&tokens,
+                                                 &PascalString15::from_utf8([b'a'; 20]).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_const_construction() {
+        const EMPTY: PascalString15 = PascalString::new();
+        static COMMANDS: [PascalString15; 2] = [pstr!("ouvrir"), pstr!("fermé")];
+        assert!(EMPTY.is_empty());
+        assert_eq!(COMMANDS[0], "ouvrir");
+        assert_eq!(COMMANDS[1].len(), 6);
+
+        let string: PascalString15 = pstr!("señor");
+        assert_eq!(string, PascalString15::from_str("señor").unwrap());
+        assert_eq!(string.get_unchecked(6), 0);
+    }
+
+    #[test]
+    fn test_format() {
+        use core::fmt::Write;
+
+        let mut string = PascalString::<8>::new();
+        write!(string, "{}ñ", 12).unwrap();
+        assert_eq!(string, "12ñ");
+        assert!(write!(string, "{:>5}", 3).is_err());
+        assert_eq!(string, "12ñ    ");
+        assert!(string.write_char('!').is_err());
+
+        let formatted: PascalString15 = pformat!("{}, {}", "señor", 1).unwrap();
+        assert_eq!(formatted, "señor, 1");
+        let result: Result<PascalString<4>, _> = pformat!("{}", "señor");
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 6, available: 4 }));
+        let result: Result<PascalString<4>, _> = pformat!("{}, {}", "señor", 1);
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 9, available: 4 }));
+
+        // Truncation never splits a character.
+        let truncated: PascalString<3> = pformat!(truncate; "{}", "señor").unwrap();
+        assert_eq!(truncated, "se");
+        let truncated: PascalString<4> = pformat!(truncate; "{}{}", "señ", "or").unwrap();
+        assert_eq!(truncated, "señ");
+
+        // Nothing is stored after the first cut, even if a later argument would fit.
+        let (a, b, c) = (String::from("abcd"), String::from("éé"), 'x');
+        let truncated: PascalString<5> = pformat!(truncate; "{}{}{}", a, b, c).unwrap();
+        assert_eq!(truncated, "abcd");
+    }
+
+    #[test]
+    fn test_split() {
+        fn collect<'a, I: Iterator<Item = &'a PascalStr>>(iter: I) -> Vec<&'a str> {
+            iter.map(|s| s.as_str()).collect()
+        }
+
+        let source = "añb·ñ·c·";
+        let string = PascalString31::from_str(source).unwrap();
+        let expected: Vec<_> = source.split('·').collect();
+        assert_eq!(collect(string.split('·')), expected);
+        let expected: Vec<_> = source.rsplit('·').collect();
+        assert_eq!(collect(string.rsplit('·')), expected);
+        let expected: Vec<_> = source.split(&['ñ', '·']).collect();
+        assert_eq!(collect(string.split(&['ñ', '·'])), expected);
+        assert_eq!(collect(string.splitn(2, "ñ")), ["a", "b·ñ·c·"]);
+        assert_eq!(collect(string.split_terminator('·')), ["añb", "ñ", "c"]);
+
+        // Empty patterns match between characters, never inside them.
+        let expected: Vec<_> = "ñé".split("").collect();
+        assert_eq!(collect(PascalString15::from_str("ñé").unwrap().split("")), expected);
+        let expected: Vec<_> = "ñé".rsplit("").collect();
+        assert_eq!(collect(PascalString15::from_str("ñé").unwrap().rsplit("")), expected);
+
+        let record = PascalString31::from_str("\u{3000}clé\t valeur ").unwrap();
+        assert_eq!(collect(record.split_whitespace()), ["clé", "valeur"]);
+        let (key, value) = record.split_once(char::is_whitespace).unwrap();
+        assert_eq!((key.as_str(), value.as_str()), ("", "clé\t valeur "));
+        let pair = PascalString15::from_str("clé=valeur").unwrap();
+        let (key, value) = pair.split_once('=').unwrap();
+        assert_eq!((key.as_str(), value.as_str()), ("clé", "valeur"));
+        assert!(pair.split_once(';').is_none());
+    }
+
+    #[test]
+    fn test_lossy_construction() {
+        let (string, loss) = PascalString::<4>::from_truncated("cañón");
+        assert_eq!(string, "cañ");
+        assert_eq!(loss.truncated(), 3);
+        let (string, loss) = PascalString::<5>::from_truncated("cañ");
+        assert_eq!(string, "cañ");
+        assert!(loss.is_lossless());
+
+        let (string, loss) = PascalString15::from_lossy(b"se\xf1or\xe2\x82", char::REPLACEMENT_CHARACTER);
+        assert_eq!(string, "se\u{fffd}or\u{fffd}");
+        assert_eq!((loss.replaced(), loss.truncated()), (2, 0));
+        let (string, loss) = PascalString::<4>::from_lossy(b"a\xffbc\xff", '?');
+        assert_eq!(string, "a?bc");
+        assert_eq!((loss.replaced(), loss.truncated()), (1, 1));
+        let (string, loss) = PascalString::<4>::from_lossy("añ\u{fffd}".as_bytes(), '?');
+        assert_eq!(string, "añ");
+        assert_eq!((loss.replaced(), loss.truncated()), (0, 3));
+    }
+
+    #[test]
+    fn test_trim_and_pad() {
+        let record = PascalString15::from_str("\u{3000}señor\t ").unwrap();
+        assert_eq!(record.trim().as_str(), "señor");
+        assert_eq!(record.trim_start().as_str(), "señor\t ");
+        assert_eq!(record.trim_end().as_str(), "\u{3000}señor");
+        let dots = PascalString15::from_str("··ñ·ñ··").unwrap();
+        assert_eq!(dots.trim_matches('·').as_str(), "ñ·ñ");
+        assert_eq!(dots.trim_start_matches("··").as_str(), "ñ·ñ··");
+        assert_eq!(dots.trim_end_matches(&['·', 'ñ']).as_str(), "");
+
+        let mut field = PascalString::<8>::from_str("ñ").unwrap();
+        field.pad_left(3, '0').unwrap();
+        assert_eq!(field, "00ñ");
+        field.pad_right(5, '·').unwrap();
+        assert_eq!(field, "00ñ··");
+        assert_eq!(field.pad_right(6, '·'), Err(PascalStringAppendError::NoRoom { required: 2, available: 0 }));
+        assert_eq!(field, "00ñ··");
+        let mut huge = PascalString::<8>::from_str("ab").unwrap();
+        assert_eq!(huge.pad_left(usize::MAX / 2 + 3, 'é'),
+                   Err(PascalStringAppendError::NoRoom { required: usize::MAX, available: 6 }));
+        assert_eq!(huge.center(usize::MAX, 'é'),
+                   Err(PascalStringAppendError::NoRoom { required: usize::MAX, available: 6 }));
+        assert_eq!(huge, "ab");
+
+        let mut title = PascalString15::from_str("ab").unwrap();
+        title.center(5, '*').unwrap();
+        assert_eq!(title, "*ab**");
+    }
+
+    #[test]
+    fn test_replace() {
+        let ident = PascalString15::from_str("año-niño").unwrap();
+        let replaced: PascalString15 = ident.replace('ñ', "n").unwrap();
+        assert_eq!(replaced, "ano-nino");
+        let replaced: PascalString15 = ident.replacen("ñ", "ny", 1).unwrap();
+        assert_eq!(replaced, "anyo-niño");
+
+        let result: Result<PascalString15, _> = ident.replace('-', "·····");
+        assert_eq!(result, Err(PascalStringAppendError::NoRoom { required: 5, available: 1 }));
+        // Truncation never splits a character.
+        let truncated: PascalString15 = ident.replace_truncated('-', "····").unwrap();
+        assert_eq!(truncated, "año····ni");
+        let cut = PascalString15::from_str("abcd-é-x").unwrap();
+        let truncated: PascalString<5> = cut.replace_truncated("-", "éé").unwrap();
+        assert_eq!(truncated, "abcd");
+        let truncated: PascalString<5> = cut.replacen_truncated("-", "éé", 1).unwrap();
+        assert_eq!(truncated, "abcd");
+
+        let mut string = PascalString15::from_str("a.b").unwrap();
+        string.replace_in_place('.', "→").unwrap();
+        assert_eq!(string, "a→b");
+        assert_eq!(string.replace_in_place('→', "→→→→→"),
+                   Err(PascalStringAppendError::NoRoom { required: 15, available: 14 }));
+        assert_eq!(string, "a→b");
+    }
+
+    #[test]
+    fn test_editing() {
+        let mut string = PascalString15::from_str("señor mío").unwrap();
+        let mio = string.split_off(7);
+        assert_eq!(string, "señor ");
+        assert_eq!(mio, "mío");
+        string.truncate(5);
+        string.truncate(10);
+        assert_eq!(string, "seño");
+
+        string.insert_str("r ", 5);
+        string.insert_str("¡", 0);
+        assert_eq!(string, "¡señor ");
+        string.replace_range(2..3, "S");
+        assert_eq!(string, "¡Señor ");
+        assert_eq!(string.try_insert_str("mío, mío", 8),
+                   Err(PascalStringAppendError::NoRoom { required: 10, available: 6 }));
+        assert_eq!(string, "¡Señor ");
+
+        string.retain(|ch| ch.is_alphabetic());
+        assert_eq!(string, "Señor");
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), "Señor".as_bytes());
+
+        {
+            let mut drain = string.drain(1..4);
+            assert_eq!(drain.as_str(), "eñ");
+            assert_eq!(drain.next_back(), Some('ñ'));
+        }
+        assert_eq!(string, "Sor");
+        let drained: String = string.drain(..).collect();
+        assert_eq!(drained, "Sor");
+        assert!(string.is_empty());
+    }
+
+    #[test]
+    fn test_error_details() {
+        let error = PascalString15::from_utf8(b"se\xc3or").unwrap_err();
+        assert_eq!(error.valid_up_to(), 2);
+        assert_eq!(error.unconsumed(b"se\xc3or"), b"\xc3or");
+        let error = PascalString15::from_str("señor, señor!!!").unwrap_err();
+        assert_eq!(error, PascalStringCreateError::InputTooLong { required: 17, available: 15 });
+        assert_eq!(error.unconsumed("señor, señor!!!"), b"!!");
+
+        // The unconsumed part starts at the beginning of the character which did not fit.
+        let mut string = PascalString15::from_str("buenos días").unwrap();
+        let error = string.try_push_str("señor").unwrap_err();
+        assert_eq!(error, PascalStringAppendError::NoRoom { required: 6, available: 3 });
+        assert_eq!(error.valid_up_to(), 3);
+        assert_eq!(error.unconsumed("señor"), "ñor".as_bytes());
+        #[cfg(feature = "alloc")]
+        assert_eq!(error.to_string(), "there is no room for the string to be appended (6 bytes required, 3 available)");
+        assert_eq!(string, "buenos días");
+    }
+
+    #[test]
+    fn test_ascii_conversion() {
+        use ::ascii;
+
+        let ascii = ascii::PascalString15::from("hello").unwrap();
+        let string = PascalString15::from(ascii.clone());
+        assert_eq!(string, "hello");
+        assert_eq!(string.len(), ascii.len());
+        assert_eq!(string, ascii);
+        assert_eq!(ascii, string);
+        assert_eq!(<&PascalStr>::from(&*ascii), &*string);
+        #[cfg(feature = "alloc")]
+        assert_eq!(string.as_cstr().unwrap().to_bytes(), b"hello");
+    }
+
+    #[test]
+    fn test_java_utf() {
+        // The output of `DataOutputStream.writeUTF("A\u0000\u20ac\ud834\udd1e")`
+        let java = [0x00, 0x0C, 0x41, 0xC0, 0x80, 0xE2, 0x82, 0xAC, 0xED, 0xA0, 0xB4, 0xED, 0xB4, 0x9E];
+        let string = PascalString15::from_str("A\0€𝄞").unwrap();
+        assert_eq!(java_utf_len(&string), 12);
+
+        let mut buf = [0u8; 16];
+        assert_eq!(encode_java_utf(&string, &mut buf), Ok(14));
+        assert_eq!(&buf[..14], &java[..]);
+        assert_eq!(encode_java_utf(&string, &mut buf[..13]), Err(JavaUtfError::NoRoom { required: 14, available: 13 }));
+
+        buf[14] = 0xFF;
+        let (decoded, rest) = decode_java_utf::<15, ::prefix::U8>(&buf[..15]).unwrap();
+        assert_eq!(decoded, string);
+        assert_eq!(rest, &[0xFF]);
+        assert_eq!(decode_java_utf::<8, ::prefix::U8>(&java), Err(JavaUtfError::NoRoom { required: 9, available: 8 }));
+        assert_eq!(decode_java_utf::<15, ::prefix::U8>(&java[..13]),
+                   Err(JavaUtfError::Truncated { required: 14, available: 13 }));
+    }
+
+    #[test]
+    fn test_java_utf_malformed() {
+        let decode = |bytes: &[u8]| decode_java_utf::<15, ::prefix::U8>(bytes).map(|(string, _)| string);
+        // A lone continuation byte
+        assert_eq!(decode(&[0x00, 0x02, 0x41, 0x80]), Err(JavaUtfError::Malformed { offset: 3 }));
+        // A four byte sequence, which modified utf8 never uses
+        assert_eq!(decode(&[0x00, 0x04, 0xF0, 0x9D, 0x84, 0x9E]), Err(JavaUtfError::Malformed { offset: 2 }));
+        // A sequence cut short by the end of the string
+        assert_eq!(decode(&[0x00, 0x03, 0x41, 0xE2, 0x82, 0xAC]), Err(JavaUtfError::Malformed { offset: 3 }));
+        // An unpaired high surrogate, then an unpaired low surrogate
+        assert_eq!(decode(&[0x00, 0x04, 0xED, 0xA0, 0xB4, 0x41]), Err(JavaUtfError::Malformed { offset: 2 }));
+        assert_eq!(decode(&[0x00, 0x04, 0x41, 0xED, 0xB4, 0x9E]), Err(JavaUtfError::Malformed { offset: 3 }));
+        #[cfg(feature = "alloc")]
+        assert_eq!(JavaUtfError::Malformed { offset: 3 }.to_string(),
+                   "the input is not valid modified utf8 at offset 3");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_truncate_inside_char() {
+        let mut string = PascalString15::from_str("señor").unwrap();
+        string.truncate(3);
+    }
 }
@@ -0,0 +1 @@
+packed_pascal_strings!(utf8);
@@ -1,74 +1,370 @@
-use std::borrow::{Cow, ToOwned};
-use std::cmp::{Ordering, PartialEq, PartialOrd};
-use std::ffi::{CStr, CString};
-use std::str;
-use ::utf8::PascalString;
-use ::PASCAL_STRING_BUF_SIZE;
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::ffi::CString;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use core::borrow::Borrow;
+use core::cmp::{Ordering, PartialEq, PartialOrd};
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeTo};
+use core::str::{self, Utf8Error};
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+use std::ascii::AsciiExt;
+#[cfg(feature = "std")]
+use std::error::Error;
+use ::ascii;
+#[cfg(feature = "alloc")]
+use ::ascii::InteriorNullError;
+use ::utf8::{PascalString, PascalStringAppendError};
+use ::utf8::{Pattern, RSplit, Split, SplitN, SplitTerminator, SplitWhitespace};
+use ::utf8::pattern::Searcher;
+use ::prefix::{LengthPrefix, U8};
 
+/// A borrowed slice from a `PascalString`. Does not own its data.
 #[derive(Hash, Eq, Ord)]
 pub struct PascalStr {
+    /// The `str`, borrowed from the original `PascalString`
     string: str
 }
 
 impl PascalStr {
+    /// Borrow a `PascalStr` from the start of a buffer which holds a pascal string with a single byte
+    /// length prefix.
+    ///
+    /// The string is not copied. On success, the `PascalStr` is returned along with the remainder of
+    /// `bytes` which follows the end of the string.
+    ///
+    /// Returns an `Err` if `bytes` is shorter than the length given by its first byte, or if the string
+    /// data is not valid utf8.
+    #[inline]
+    pub fn from_prefixed_bytes(bytes: &[u8]) -> Result<(&PascalStr, &[u8]), PrefixedBytesError> {
+        PascalStr::from_prefixed_bytes_with::<U8>(bytes)
+    }
+
+    /// Borrow a `PascalStr` from the start of a buffer which holds a pascal string with the length
+    /// prefix `L`.
+    ///
+    /// See `PascalStr::from_prefixed_bytes()` for details.
+    pub fn from_prefixed_bytes_with<L: LengthPrefix>(bytes: &[u8])
+                                                     -> Result<(&PascalStr, &[u8]), PrefixedBytesError> {
+        if bytes.len() < L::WIDTH {
+            return Err(PrefixedBytesError::Truncated { required: L::WIDTH, available: bytes.len() });
+        }
+        let len = L::to_usize(L::read_len(bytes));
+        if bytes.len() < L::WIDTH + len {
+            return Err(PrefixedBytesError::Truncated { required: L::WIDTH + len, available: bytes.len() });
+        }
+        let bytes = &bytes[L::WIDTH..];
+        let (string, rest) = bytes.split_at(len);
+        let string = str::from_utf8(string)?;
+        Ok((From::from(string), rest))
+    }
+
+    /// Get a pointer to the first byte of the string buffer.
     #[inline]
     pub fn as_ptr(&self) -> *const u8 {
-        (&self.string).as_ptr()
+        self.string.as_ptr()
     }
 
+    /// Get a mutable pointer to the first byte of the string buffer.
     #[inline]
     pub fn as_mut_ptr(&mut self) -> *mut u8 {
         &mut self.string as *mut str as *mut u8
     }
 
+    /// Get the `PascalStr` as an immutable `&str` reference.
     #[inline]
     pub fn as_str(&self) -> &str {
         &self.string
     }
 
+    /// Get the `PascalStr` as a mutable `&mut str` reference.
     #[inline]
     pub fn as_mut_str(&mut self) -> &mut str {
         &mut self.string
     }
 
+    /// Get the utf8 encoded bytes of the `PascalStr`.
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
         self.string.as_bytes()
     }
 
-    pub fn as_cstr(&self) -> Result<Cow<CStr>, InteriorNullError> {
-        unimplemented!()
+    /// Get this string as a `CStr`.
+    ///
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If the last character of
+    /// this string is not a null character, then a new `CString` will be allocated to hold the trailing null
+    /// byte.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        match self.bytes().position(|b| b == 0) {
+            Some(pos) if pos != (self.len() - 1) => Err(InteriorNullError::new(pos)),
+            Some(_) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(self.as_bytes()).unwrap())),
+            None => Ok(Cow::Owned(CString::new(self.as_str()).unwrap()))
+        }
     }
 
+    /// Returns the number of bytes used in the string.
+    ///
+    /// Note that this is not the same as the number of characters in the string.
     #[inline]
     pub fn len(&self) -> usize {
         self.string.len()
     }
 
+    /// Returns true if the string has a length of 0
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.string.is_empty()
     }
 
+    /// Returns true if `index` is on a character boundary, as with `str::is_char_boundary`.
     #[inline]
-    pub fn is_full(&self) -> bool {
-        self.len() == PASCAL_STRING_BUF_SIZE
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        self.string.is_char_boundary(index)
     }
 
+    /// Get an iterator over the characters of the string.
     #[inline]
-    pub fn chars(&self) -> Chars {
+    pub fn chars(&self) -> Chars<'_> {
         self.string.chars()
     }
 
+    /// Get an iterator over the bytes of the string.
     #[inline]
-    pub fn bytes(&self) -> Bytes {
+    pub fn bytes(&self) -> Bytes<'_> {
         self.string.bytes()
     }
 
+    /// Get an iterator over the lines of the string.
     #[inline]
-    pub fn lines(&self) -> Lines {
+    pub fn lines(&self) -> Lines<'_> {
         self.string.lines()
     }
+
+    /// Returns an iterator over the parts of this string separated by `pat`.
+    #[inline]
+    pub fn split<P: Pattern>(&self, pat: P) -> Split<'_, P> {
+        Split::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the parts of this string separated by `pat`, starting from the end.
+    #[inline]
+    pub fn rsplit<P: Pattern>(&self, pat: P) -> RSplit<'_, P> {
+        RSplit::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over at most `n` parts of this string separated by `pat`. The last part
+    /// contains the remainder of the string.
+    #[inline]
+    pub fn splitn<P: Pattern>(&self, n: usize, pat: P) -> SplitN<'_, P> {
+        SplitN::new(&self.string, pat, n)
+    }
+
+    /// Returns an iterator over the parts of this string separated by `pat`, without an empty part after
+    /// a trailing match.
+    #[inline]
+    pub fn split_terminator<P: Pattern>(&self, pat: P) -> SplitTerminator<'_, P> {
+        SplitTerminator::new(&self.string, pat)
+    }
+
+    /// Returns an iterator over the parts of this string separated by any amount of unicode whitespace.
+    #[inline]
+    pub fn split_whitespace(&self) -> SplitWhitespace<'_> {
+        SplitWhitespace::new(&self.string)
+    }
+
+    /// Splits this string around the first match of `pat`, or returns `None` if it does not match.
+    #[inline]
+    pub fn split_once<P: Pattern>(&self, pat: P) -> Option<(&PascalStr, &PascalStr)> {
+        let string: &str = &self.string;
+        Searcher::new(string, pat).next_match().map(|(start, len)| {
+            (From::from(&string[..start]), From::from(&string[start + len..]))
+        })
+    }
+
+    /// Returns this string with leading and trailing whitespace removed.
+    #[inline]
+    pub fn trim(&self) -> &PascalStr {
+        From::from(self.string.trim())
+    }
+
+    /// Returns this string with leading whitespace removed.
+    #[inline]
+    pub fn trim_start(&self) -> &PascalStr {
+        From::from(self.string.trim_start())
+    }
+
+    /// Returns this string with trailing whitespace removed.
+    #[inline]
+    pub fn trim_end(&self) -> &PascalStr {
+        From::from(self.string.trim_end())
+    }
+
+    /// Returns this string with all leading and trailing matches of `pat` removed.
+    #[inline]
+    pub fn trim_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let start = self.trim_start_index(&mut pat);
+        let end = self.trim_end_index(&mut pat, start);
+        From::from(&self.string[start..end])
+    }
+
+    /// Returns this string with all leading matches of `pat` removed.
+    #[inline]
+    pub fn trim_start_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let start = self.trim_start_index(&mut pat);
+        From::from(&self.string[start..])
+    }
+
+    /// Returns this string with all trailing matches of `pat` removed.
+    #[inline]
+    pub fn trim_end_matches<P: Pattern>(&self, mut pat: P) -> &PascalStr {
+        let end = self.trim_end_index(&mut pat, 0);
+        From::from(&self.string[..end])
+    }
+
+    /// The byte index of the first character which is not part of a match of `pat` at the start of the
+    /// string.
+    fn trim_start_index<P: Pattern>(&self, pat: &mut P) -> usize {
+        let mut start = 0;
+        loop {
+            match pat.match_at(&self.string, start) {
+                Some(len) if len > 0 => start += len,
+                _ => return start
+            }
+        }
+    }
+
+    /// The byte index after the last character which is not part of a match of `pat` at the end of the
+    /// string, which is never less than `start`.
+    fn trim_end_index<P: Pattern>(&self, pat: &mut P, start: usize) -> usize {
+        let mut end = self.len();
+        loop {
+            let boundaries = self.string[start..end].char_indices().rev().map(|(i, _)| start + i);
+            match boundaries.clone().find(|&i| pat.match_at(&self.string, i) == Some(end - i)) {
+                Some(i) => end = i,
+                None => return end
+            }
+        }
+    }
+
+    /// Replace all matches of `from` with `to`, writing the result into a new `PascalString`. The capacity
+    /// and length prefix of the result are taken from the type it is used as.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the `PascalString`.
+    #[inline]
+    pub fn replace<P, S, const N: usize, L>(&self, from: P, to: S)
+                                            -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), usize::MAX, false)
+    }
+
+    /// Replace the first `count` matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The capacity and length prefix of the result are taken from the type it is used as.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the `PascalString`.
+    #[inline]
+    pub fn replacen<P, S, const N: usize, L>(&self, from: P, to: S, count: usize)
+                                             -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), count, false)
+    }
+
+    /// Replace all matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The result is truncated to the capacity of the `PascalString`, without splitting a character.
+    #[inline]
+    pub fn replace_truncated<P, S, const N: usize, L>(&self, from: P, to: S)
+                                                      -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), usize::MAX, true)
+    }
+
+    /// Replace the first `count` matches of `from` with `to`, writing the result into a new `PascalString`.
+    /// The result is truncated to the capacity of the `PascalString`, without splitting a character.
+    #[inline]
+    pub fn replacen_truncated<P, S, const N: usize, L>(&self, from: P, to: S, count: usize)
+                                                       -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, S: AsRef<str>, L: LengthPrefix {
+        self.replace_into(from, to.as_ref(), count, true)
+    }
+
+    fn replace_into<P, const N: usize, L>(&self, from: P, to: &str, count: usize, truncate: bool)
+                                          -> Result<PascalString<N, L>, PascalStringAppendError>
+        where P: Pattern, L: LengthPrefix {
+        let string: &str = &self.string;
+        let mut result = PascalString::new();
+        {
+            // Returns `Ok(false)` once the result has been truncated, after which nothing more is pushed, as a
+            // later, shorter piece could still fit after a character which did not.
+            let mut push = |s: &str| if truncate {
+                Ok(result.push_str_truncated(s))
+            } else {
+                result.try_push_str(s).map(|()| true)
+            };
+            let mut searcher = Searcher::new(string, from);
+            let mut last = 0;
+            for _ in 0..count {
+                let (start, len) = match searcher.next_match() {
+                    Some(found) => found,
+                    None => break
+                };
+                if !push(&string[last..start])? || !push(to)? {
+                    return Ok(result);
+                }
+                last = start + len;
+            }
+            let _ = push(&string[last..])?;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+impl AsciiExt for PascalStr {
+    type Owned = String;
+
+    fn is_ascii(&self) -> bool {
+        self.string.is_ascii()
+    }
+
+    fn to_ascii_uppercase(&self) -> Self::Owned {
+        self.string.to_ascii_uppercase()
+    }
+
+    fn to_ascii_lowercase(&self) -> Self::Owned {
+        self.string.to_ascii_lowercase()
+    }
+
+    fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
+        self.string.eq_ignore_ascii_case(&other.string)
+    }
+
+    fn make_ascii_uppercase(&mut self) {
+        self.string.make_ascii_uppercase()
+    }
+
+    fn make_ascii_lowercase(&mut self) {
+        self.string.make_ascii_lowercase()
+    }
+}
+
+impl fmt::Debug for PascalStr {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.pad(self.as_str())
+    }
+}
+
+impl fmt::Display for PascalStr {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.pad(self.as_str())
+    }
 }
 
 impl<S: AsRef<str> + ?Sized> PartialEq<S> for PascalStr {
@@ -83,15 +379,65 @@ impl<S: AsRef<str> + ?Sized> PartialOrd<S> for PascalStr {
     #[inline]
     fn partial_cmp(&self, other: &S) -> Option<Ordering> {
         let other = other.as_ref();
-        self.as_str().partial_cmp(&other)
+        self.as_str().partial_cmp(other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ToOwned for PascalStr {
-    type Owned = PascalString;
+    /// A `PascalStr` may be longer than the default `PascalString` can hold, so it is copied into a `String`.
+    type Owned = String;
     #[inline]
     fn to_owned(&self) -> Self::Owned {
-        PascalString::from_str(self.as_str()).unwrap()
+        String::from(&self.string)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow<PascalStr> for String {
+    #[inline]
+    fn borrow(&self) -> &PascalStr {
+        From::from(self.as_str())
+    }
+}
+
+impl Default for &PascalStr {
+    #[inline]
+    fn default() -> Self {
+        From::from("")
+    }
+}
+
+impl<'a> From<&'a str> for &'a PascalStr {
+    #[inline]
+    fn from(string: &'a str) -> Self {
+        unsafe {
+            mem::transmute(string)
+        }
+    }
+}
+
+impl<'a> From<&'a mut str> for &'a mut PascalStr {
+    #[inline]
+    fn from(string: &'a mut str) -> Self {
+        unsafe {
+            mem::transmute(string)
+        }
+    }
+}
+
+impl<'a> From<&'a ascii::PascalStr> for &'a PascalStr {
+    /// Borrows an ascii `PascalStr` as a `utf8::PascalStr`. Ascii is valid utf8, so this cannot fail.
+    #[inline]
+    fn from(string: &'a ascii::PascalStr) -> Self {
+        From::from(string.as_str())
+    }
+}
+
+impl AsRef<PascalStr> for PascalStr {
+    #[inline]
+    fn as_ref(&self) -> &Self {
+        self
     }
 }
 
@@ -102,8 +448,248 @@ impl AsRef<str> for PascalStr {
     }
 }
 
+impl AsMut<str> for PascalStr {
+    #[inline]
+    fn as_mut(&mut self) -> &mut str {
+        &mut self.string
+    }
+}
+
+impl AsRef<[u8]> for PascalStr {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.string.as_bytes()
+    }
+}
+
+impl Index<RangeFull> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, _: RangeFull) -> &Self::Output {
+        &self.string[..]
+    }
+}
+
+impl IndexMut<RangeFull> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, _: RangeFull) -> &mut Self::Output {
+        &mut self.string[..]
+    }
+}
+
+impl Index<Range<u8>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: Range<u8>) -> &Self::Output {
+        &self.string[range.start as usize..range.end as usize]
+    }
+}
+
+impl IndexMut<Range<u8>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: Range<u8>) -> &mut Self::Output {
+        &mut self.string[range.start as usize..range.end as usize]
+    }
+}
+
+impl Index<Range<usize>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        &self.string[range]
+    }
+}
+
+impl IndexMut<Range<usize>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: Range<usize>) -> &mut Self::Output {
+        &mut self.string[range]
+    }
+}
+
+impl Index<Range<i32>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: Range<i32>) -> &Self::Output {
+        assert!(range.start >= 0);
+        assert!(range.end >= 0);
+        &self.string[range.start as usize..range.end as usize]
+    }
+}
+
+impl IndexMut<Range<i32>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: Range<i32>) -> &mut Self::Output {
+        assert!(range.start >= 0);
+        assert!(range.end >= 0);
+        &mut self.string[range.start as usize..range.end as usize]
+    }
+}
+
+impl Index<RangeFrom<u8>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeFrom<u8>) -> &Self::Output {
+        &self.string[range.start as usize..]
+    }
+}
+
+impl IndexMut<RangeFrom<u8>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeFrom<u8>) -> &mut Self::Output {
+        &mut self.string[range.start as usize..]
+    }
+}
+
+impl Index<RangeFrom<usize>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeFrom<usize>) -> &Self::Output {
+        &self.string[range]
+    }
+}
+
+impl IndexMut<RangeFrom<usize>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeFrom<usize>) -> &mut Self::Output {
+        &mut self.string[range]
+    }
+}
+
+impl Index<RangeFrom<i32>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeFrom<i32>) -> &Self::Output {
+        assert!(range.start >= 0);
+        &self.string[range.start as usize..]
+    }
+}
+
+impl IndexMut<RangeFrom<i32>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeFrom<i32>) -> &mut Self::Output {
+        assert!(range.start >= 0);
+        &mut self.string[range.start as usize..]
+    }
+}
+
+impl Index<RangeTo<u8>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeTo<u8>) -> &Self::Output {
+        &self.string[..range.end as usize]
+    }
+}
+
+impl IndexMut<RangeTo<u8>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeTo<u8>) -> &mut Self::Output {
+        &mut self.string[..range.end as usize]
+    }
+}
+
+impl Index<RangeTo<usize>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeTo<usize>) -> &Self::Output {
+        &self.string[range]
+    }
+}
+
+impl IndexMut<RangeTo<usize>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeTo<usize>) -> &mut Self::Output {
+        &mut self.string[range]
+    }
+}
+
+impl Index<RangeTo<i32>> for PascalStr {
+    type Output = str;
+    #[inline]
+    fn index(&self, range: RangeTo<i32>) -> &Self::Output {
+        assert!(range.end >= 0);
+        &self.string[..range.end as usize]
+    }
+}
+
+impl IndexMut<RangeTo<i32>> for PascalStr {
+    #[inline]
+    fn index_mut(&mut self, range: RangeTo<i32>) -> &mut Self::Output {
+        assert!(range.end >= 0);
+        &mut self.string[..range.end as usize]
+    }
+}
+
+impl<'a> IntoIterator for &'a PascalStr {
+    type Item = char;
+    type IntoIter = Chars<'a>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.chars()
+    }
+}
+
+/// An iterator over the characters of a `PascalStr`.
 pub type Chars<'a> = str::Chars<'a>;
+
+/// An iterator over the bytes of a `PascalStr`.
 pub type Bytes<'a> = str::Bytes<'a>;
+
+/// An iterator over the lines of a `PascalStr`.
 pub type Lines<'a> = str::Lines<'a>;
 
-pub struct InteriorNullError;
+/// Indicates the range of errors which can occur when borrowing a `PascalStr` from a length prefixed buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixedBytesError {
+    /// The buffer is too short to hold the length prefix, or the string data which follows it.
+    Truncated {
+        /// The number of bytes needed to hold the length prefix, or the length prefix and the string data.
+        required: usize,
+        /// The length of the buffer.
+        available: usize
+    },
+    /// The string data was not correctly encoded as utf8.
+    NotValidUtf8(Utf8Error)
+}
+
+impl PrefixedBytesError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PrefixedBytesError::Truncated { .. } => "the buffer is shorter than the length prefix of the string",
+            PrefixedBytesError::NotValidUtf8(_) => "the input data is not valid utf8"
+        }
+    }
+}
+
+impl fmt::Display for PrefixedBytesError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PrefixedBytesError::Truncated { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            PrefixedBytesError::NotValidUtf8(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PrefixedBytesError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PrefixedBytesError::NotValidUtf8(ref e) = *self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Utf8Error> for PrefixedBytesError {
+    #[inline]
+    fn from(e: Utf8Error) -> Self {
+        PrefixedBytesError::NotValidUtf8(e)
+    }
+}
@@ -1,30 +1,72 @@
-use std::borrow::{Borrow, BorrowMut, Cow};
-use std::cmp::{Eq, PartialEq, Ord, Ordering, PartialOrd};
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::{self, Eq, PartialEq, Ord, Ordering, PartialOrd};
+#[cfg(feature = "alloc")]
+use core::ffi::CStr;
+use core::hash::{Hash, Hasher};
+use core::iter::{DoubleEndedIterator, FromIterator, IntoIterator};
+use core::ops::{Deref, DerefMut, Range, RangeBounds};
+use core::str::{self, FromStr, Utf8Error};
+use core::{fmt, mem, ptr};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+use std::ascii::AsciiExt;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::hash::{Hash, Hasher};
-use std::ops::{Deref, DerefMut};
-use std::{fmt, mem, ptr, str};
-use odds::char::{encode_utf8, EncodeUtf8Error};
-use ::utf8::PascalStr;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+use odds::char::encode_utf8;
+#[cfg(feature = "alloc")]
+use ::ascii::InteriorNullError;
+use ::ascii;
+use ::utf8::{PascalStr, Pattern};
+use ::prefix::{LengthPrefix, U8};
+use ::ConversionLoss;
+#[cfg(feature = "std")]
+use ::Layout;
 use ::PASCAL_STRING_BUF_SIZE;
 
-/// An owned `PascalString`. This string type stores its data the stack. It is always 256 bytes long, with
-/// the first byte storing the length *of the number of bytes used*.
+/// An owned `PascalString`. This string type stores its data the stack. With the default length prefix, it is
+/// always `N + 1` bytes long, with the first byte storing the length *of the number of bytes used*.
+///
+/// The capacity `N` is measured in bytes, and defaults to 255. It may not be larger than the maximum length
+/// which can be stored in the length prefix `L`, which is 255 for the default single byte prefix.
 ///
 /// Note that because this string type is utf8 encoded, the first byte will not store the number of characters
 /// in the string.
-pub struct PascalString {
+pub struct PascalString<const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
     /// The number of bytes used in the string.
-    len: u8,
+    len: L::Len,
     /// The internal character buffer, encoded in utf8
-    chars_buf: [u8; PASCAL_STRING_BUF_SIZE]
+    chars_buf: [u8; N]
 }
 
-impl PascalString {
+/// A `PascalString` which can hold up to 15 bytes.
+pub type PascalString15 = PascalString<15>;
+
+/// A `PascalString` which can hold up to 31 bytes.
+pub type PascalString31 = PascalString<31>;
+
+/// A `PascalString` which can hold up to 63 bytes.
+pub type PascalString63 = PascalString<63>;
+
+/// A `PascalString` which can hold up to 255 bytes.
+pub type PascalString255 = PascalString<255>;
+
+impl<const N: usize, L: LengthPrefix> PascalString<N, L> {
     /// Creates a new, empty `PascalString`.
     #[inline]
-    pub fn new() -> Self {
-        Default::default()
+    pub const fn new() -> Self {
+        let () = ::CapacityCheck::<L, N>::VALID;
+        PascalString {
+            len: L::EMPTY,
+            chars_buf: [0u8; N]
+        }
     }
 
     /// Creates a new `PascalString` with the contents of `s`.
@@ -35,17 +77,133 @@ impl PascalString {
     /// `Ok`. Otherwise, returns `Err`.
     #[inline]
     pub fn from_str<S: AsRef<str>>(s: S) -> Result<Self, PascalStringCreateError> {
-        let s = s.as_ref();
-        if s.len() > PASCAL_STRING_BUF_SIZE {
-            return Err(PascalStringCreateError::InputTooLong);
+        PascalString::_from_str(s.as_ref())
+    }
+
+    fn _from_str(s: &str) -> Result<Self, PascalStringCreateError> {
+        let len = s.len();
+        if len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: len, available: N });
         }
         let mut pstring = PascalString::new();
-        for ch in s.chars() {
-            pstring.push(ch)
-        }
+        pstring.chars_buf[..len].copy_from_slice(s.as_bytes());
+        pstring.set_len(len);
         Ok(pstring)
     }
 
+    /// Create a new `PascalString` using the contents of `bytes`.
+    ///
+    /// Returns an `Err` if `bytes` is longer than the capacity of the string, or it does not contain
+    /// valid utf8.
+    #[inline]
+    pub fn from_utf8<B: AsRef<[u8]>>(bytes: B) -> Result<Self, PascalStringCreateError> {
+        PascalString::_from_utf8(bytes.as_ref())
+    }
+
+    fn _from_utf8(bytes: &[u8]) -> Result<Self, PascalStringCreateError> {
+        if bytes.len() > N {
+            return Err(PascalStringCreateError::InputTooLong { required: bytes.len(), available: N });
+        }
+        PascalString::_from_str(str::from_utf8(bytes)?)
+    }
+
+    /// Create a new `PascalString` from its constituent parts: `string_len` and `bytes`.
+    ///
+    /// Returns an `Err` if `string_len` is larger than the capacity of the string, or if the first
+    /// `string_len` bytes of `bytes` are not valid utf8.
+    #[inline]
+    pub fn from_fixed_utf8_array(string_len: usize, bytes: [u8; N]) -> Result<Self, PascalStringCreateError> {
+        if string_len > N {
+            return Err(PascalStringCreateError::InputTooLong { required: string_len, available: N });
+        }
+        PascalString::_from_utf8(&bytes[..string_len])
+    }
+
+    /// Create a new `PascalString` using as much of `s` as will fit into it without splitting a character,
+    /// along with a `ConversionLoss` which reports how many bytes were cut off.
+    #[inline]
+    pub fn from_truncated<S: AsRef<str>>(s: S) -> (Self, ConversionLoss) {
+        let s = s.as_ref();
+        let mut string = PascalString::new();
+        let _ = string.push_str_truncated(s);
+        let truncated = s.len() - string.len();
+        (string, ConversionLoss { truncated: truncated, replaced: 0 })
+    }
+
+    /// Create a new `PascalString` from `bytes`, replacing each invalid utf8 sequence with `replacement`
+    /// (typically `char::REPLACEMENT_CHARACTER`), and cutting off any characters which do not fit into the
+    /// string.
+    ///
+    /// The returned `ConversionLoss` reports how many invalid sequences were replaced, and how many bytes were
+    /// cut off.
+    pub fn from_lossy<B: AsRef<[u8]>>(bytes: B, replacement: char) -> (Self, ConversionLoss) {
+        let bytes = bytes.as_ref();
+        let mut string = PascalString::new();
+        let mut loss = ConversionLoss::default();
+        let mut consumed = 0;
+        while consumed < bytes.len() {
+            let rest = &bytes[consumed..];
+            let (valid, invalid_len) = match str::from_utf8(rest) {
+                Ok(valid) => (valid, 0),
+                Err(e) => {
+                    let valid = str::from_utf8(&rest[..e.valid_up_to()]).unwrap();
+                    (valid, e.error_len().unwrap_or(rest.len() - e.valid_up_to()))
+                }
+            };
+            let before = string.len();
+            let _ = string.push_str_truncated(valid);
+            consumed += string.len() - before;
+            if string.len() - before < valid.len() {
+                break;
+            }
+            if invalid_len > 0 {
+                if string.try_push(replacement).is_err() {
+                    break;
+                }
+                loss.replaced += 1;
+                consumed += invalid_len;
+            }
+        }
+        loss.truncated = bytes.len() - consumed;
+        (string, loss)
+    }
+
+    /// Create a new `PascalString` from formatted text. This is usually called through the `pformat!`
+    /// macro.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the formatted text is longer than the capacity of the
+    /// string.
+    #[inline]
+    pub fn format(args: fmt::Arguments) -> Result<Self, PascalStringAppendError> {
+        PascalString::_format(args, false)
+    }
+
+    /// Create a new `PascalString` from formatted text, which is truncated to the capacity of the string.
+    /// This is usually called through the `pformat!` macro.
+    ///
+    /// This never returns an `Err`, but has the same signature as `format()` for the benefit of `pformat!`.
+    #[inline]
+    pub fn format_truncated(args: fmt::Arguments) -> Result<Self, PascalStringAppendError> {
+        PascalString::_format(args, true)
+    }
+
+    fn _format(args: fmt::Arguments, truncate: bool) -> Result<Self, PascalStringAppendError> {
+        let mut writer = FormatWriter {
+            string: PascalString::new(),
+            truncate: truncate,
+            truncated: false,
+            required: 0,
+            error: None
+        };
+        match fmt::write(&mut writer, args) {
+            Ok(()) if writer.required > N && !truncate => {
+                Err(PascalStringAppendError::NoRoom { required: writer.required, available: N })
+            }
+            Ok(()) => Ok(writer.string),
+            Err(_) => Err(writer.error.expect("a formatting trait implementation returned an error"))
+        }
+    }
+
     /// Push a character onto the end of the string's internal buffer.
     ///
     /// # Panics
@@ -62,9 +220,15 @@ impl PascalString {
     ///
     /// Returns `Ok(())` if the operation succeeded, otherwise an error is returned.
     #[inline]
-    pub fn try_push(&mut self, ch: char) -> Result<(), EncodeUtf8Error> {
-        let num_bytes_extended = try!(encode_utf8(ch, &mut self.chars_buf[self.len as usize..]));
-        self.len += num_bytes_extended as u8;
+    pub fn try_push(&mut self, ch: char) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        let ch_len = ch.len_utf8();
+        if len + ch_len > N {
+            return Err(PascalStringAppendError::NoRoom { required: ch_len, available: N - len });
+        }
+        let _ = encode_utf8(ch, &mut self.chars_buf[len..]);
+        self.set_len(len + ch_len);
+        self.set_trailing_byte_to_null();
         Ok(())
     }
 
@@ -89,17 +253,445 @@ impl PascalString {
     }
 
     fn _try_push_str(&mut self, s: &str) -> Result<(), PascalStringAppendError> {
-        if self.len() + s.len() > PASCAL_STRING_BUF_SIZE {
-            return Err(PascalStringAppendError::NoRoom);
+        if self.len() + s.len() > N {
+            return Err(PascalStringAppendError::NoRoom { required: s.len(), available: N - self.len() });
         }
         for ch in s.chars() {
-            try!(self.try_push(ch))
+            self.try_push(ch)?
+        }
+        Ok(())
+    }
+
+    /// Append as much of `s` as will fit onto the end of this string, without splitting a character.
+    /// Returns `false` if some of `s` was cut off.
+    pub(crate) fn push_str_truncated(&mut self, s: &str) -> bool {
+        let mut end = ::core::cmp::min(s.len(), N - self.len());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self._try_push_str(&s[..end]).is_ok() && end == s.len()
+    }
+
+    /// Removes the last character from the string buffer and returns it.
+    ///
+    /// Returns `None` if this `PascalString` is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let len = self.len() - ch.len_utf8();
+        self.set_len(len);
+        self.set_trailing_byte_to_null();
+        Some(ch)
+    }
+
+    /// Remove the character which starts at the byte position `index`, and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than or equal to `self.len()`, or if it does not lie on a character boundary.
+    pub fn remove(&mut self, index: usize) -> char {
+        let len = self.len();
+        let ch = match self[index..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string")
+        };
+        let ch_len = ch.len_utf8();
+        // Shift everything to the right of the removed character to the left to cover up the hole
+        // left.
+        unsafe {
+            let ptr = self.chars_buf.as_mut_ptr().add(index);
+            ptr::copy(ptr.add(ch_len), ptr, len - index - ch_len);
+        }
+        self.set_len(len - ch_len);
+        self.set_trailing_byte_to_null();
+        ch
+    }
+
+    /// Insert a character into the `PascalString` at the byte position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`, if it does not lie on a character boundary, or if there
+    /// is no room to store the `char`.
+    #[inline]
+    pub fn insert(&mut self, ch: char, index: usize) {
+        self.try_insert(ch, index).unwrap()
+    }
+
+    /// Attempt to insert a character into the `PascalString` at the byte position `index`.
+    ///
+    /// Returns `Err(_)` if there is no room to store the `char`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`, or if it does not lie on a character boundary.
+    pub fn try_insert(&mut self, ch: char, index: usize) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        assert!(self.is_char_boundary(index));
+        let ch_len = ch.len_utf8();
+        if len + ch_len > N {
+            return Err(PascalStringAppendError::NoRoom { required: ch_len, available: N - len });
+        }
+        // Shift everything to the right of `index` to the right to make room for the new character.
+        unsafe {
+            let ptr = self.chars_buf.as_mut_ptr().add(index);
+            ptr::copy(ptr, ptr.add(ch_len), len - index);
+        }
+        let _ = encode_utf8(ch, &mut self.chars_buf[index..]);
+        self.set_len(len + ch_len);
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Truncates this String, removing all contents.
+    ///
+    /// Does not zero the values of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set_len(0);
+        self.set_trailing_byte_to_null();
+    }
+
+    /// Shortens this string to `new_len` bytes.
+    ///
+    /// Has no effect if `new_len` is greater than or equal to the length of the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a character boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.set_len(new_len);
+            self.set_trailing_byte_to_null();
+        }
+    }
+
+    /// Splits this string in two at the byte position `at`. This string is left holding the bytes before
+    /// `at`, and the bytes from `at` onwards are returned in a new `PascalString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than `self.len()`, or if it does not lie on a character boundary.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(self.is_char_boundary(at));
+        let mut other = PascalString::new();
+        other.chars_buf[..len - at].copy_from_slice(&self.chars_buf[at..len]);
+        other.set_len(len - at);
+        self.truncate(at);
+        other
+    }
+
+    /// Removes the bytes in `range` from this string, and returns the removed characters as an iterator.
+    ///
+    /// The characters are removed when the iterator is dropped, even if it has not been fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or if it does not start and end on character boundaries.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, N, L> {
+        let range = ::resolve_range(range, self.len());
+        assert!(self.is_char_boundary(range.start) && self.is_char_boundary(range.end));
+        Drain {
+            string: self,
+            start: range.start,
+            end: range.end,
+            front: range.start,
+            back: range.end
+        }
+    }
+
+    /// Retains only the characters for which `f` returns `true`, removing the rest.
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        let len = self.len();
+        // If `f` panics, the string is left empty rather than holding partially moved characters.
+        self.set_len(0);
+        let (mut read, mut kept) = (0, 0);
+        while read < len {
+            // Only the bytes before `read` are overwritten, so the rest of the buffer is still valid utf8.
+            let ch = unsafe { str::from_utf8_unchecked(&self.chars_buf[read..len]) }.chars().next().unwrap();
+            let ch_len = ch.len_utf8();
+            if f(ch) {
+                self.chars_buf.copy_within(read..read + ch_len, kept);
+                kept += ch_len;
+            }
+            read += ch_len;
         }
+        self.set_len(kept);
+        self.set_trailing_byte_to_null();
+    }
+
+    /// Insert the string slice `s` into this string at the byte position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`, if it does not lie on a character boundary, or if there
+    /// is no room to store `s`.
+    #[inline]
+    pub fn insert_str<S: AsRef<str>>(&mut self, s: S, index: usize) {
+        self.try_insert_str(s, index).unwrap()
+    }
+
+    /// Attempt to insert the string slice `s` into this string at the byte position `index`.
+    ///
+    /// Returns `Err(_)` if there is no room to store `s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than `self.len()`, or if it does not lie on a character boundary.
+    #[inline]
+    pub fn try_insert_str<S: AsRef<str>>(&mut self, s: S, index: usize) -> Result<(), PascalStringAppendError> {
+        self._try_replace_range(index..index, s.as_ref())
+    }
+
+    /// Replace the bytes in `range` with the string slice `s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, if it does not start and end on character boundaries, or if
+    /// there is no room to store `s`.
+    #[inline]
+    pub fn replace_range<R: RangeBounds<usize>, S: AsRef<str>>(&mut self, range: R, s: S) {
+        self.try_replace_range(range, s).unwrap()
+    }
+
+    /// Attempt to replace the bytes in `range` with the string slice `s`.
+    ///
+    /// Returns `Err(_)` if there is no room to store `s`, in which case the string is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or if it does not start and end on character boundaries.
+    #[inline]
+    pub fn try_replace_range<R: RangeBounds<usize>, S: AsRef<str>>(&mut self, range: R, s: S)
+                                                                   -> Result<(), PascalStringAppendError> {
+        let range = ::resolve_range(range, self.len());
+        self._try_replace_range(range, s.as_ref())
+    }
+
+    /// Replace all matches of `from` with `to` in this string.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the result does not fit into the string. The
+    /// string is left unchanged if an error is returned.
+    #[inline]
+    pub fn replace_in_place<P: Pattern, S: AsRef<str>>(&mut self, from: P, to: S)
+                                                       -> Result<(), PascalStringAppendError> {
+        let replaced: PascalString<N, L> = self.replace(from, to)?;
+        *self = replaced;
+        Ok(())
+    }
+
+    /// Pad the start of this string with `fill` until it is `width` characters long, right-justifying its
+    /// contents. Note that `width` is measured in characters, not bytes.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the padded string does not fit into the capacity of
+    /// the string. Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn pad_left(&mut self, width: usize, fill: char) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.chars().count());
+        self.pad(padding, 0, fill)
+    }
+
+    /// Pad the end of this string with `fill` until it is `width` characters long, left-justifying its
+    /// contents. Note that `width` is measured in characters, not bytes.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the padded string does not fit into the capacity of
+    /// the string. Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn pad_right(&mut self, width: usize, fill: char) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.chars().count());
+        self.pad(0, padding, fill)
+    }
+
+    /// Pad both ends of this string with `fill` until it is `width` characters long, centering its contents.
+    /// If the padding cannot be split evenly, the extra character goes at the end. Note that `width` is
+    /// measured in characters, not bytes.
+    ///
+    /// Returns `Err(PascalStringAppendError::NoRoom)` if the padded string does not fit into the capacity of
+    /// the string. Has no effect if the string is already at least `width` characters long.
+    #[inline]
+    pub fn center(&mut self, width: usize, fill: char) -> Result<(), PascalStringAppendError> {
+        let padding = width.saturating_sub(self.chars().count());
+        self.pad(padding / 2, padding - padding / 2, fill)
+    }
+
+    /// Add `left` copies of `fill` to the start of the string, and `right` copies to the end.
+    fn pad(&mut self, left: usize, right: usize, fill: char) -> Result<(), PascalStringAppendError> {
+        let mut buf = [0u8; 4];
+        let fill = fill.encode_utf8(&mut buf).as_bytes();
+        let len = self.len();
+        let padding = left.checked_add(right).and_then(|padding| padding.checked_mul(fill.len()));
+        match padding {
+            Some(padding) if padding <= N - len => { }
+            _ => {
+                let required = padding.unwrap_or(usize::MAX);
+                return Err(PascalStringAppendError::NoRoom { required: required, available: N - len });
+            }
+        }
+        let (left, right) = (left * fill.len(), right * fill.len());
+        self.chars_buf.copy_within(0..len, left);
+        for chunk in self.chars_buf[..left].chunks_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        for chunk in self.chars_buf[left + len..left + len + right].chunks_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        self.set_len(len + left + right);
+        self.set_trailing_byte_to_null();
         Ok(())
     }
+
+    fn _try_replace_range(&mut self, range: Range<usize>, s: &str) -> Result<(), PascalStringAppendError> {
+        let len = self.len();
+        assert!(range.start <= range.end && range.end <= len);
+        assert!(self.is_char_boundary(range.start) && self.is_char_boundary(range.end));
+        let kept = len - (range.end - range.start);
+        if kept + s.len() > N {
+            return Err(PascalStringAppendError::NoRoom { required: s.len(), available: N - kept });
+        }
+        self.chars_buf.copy_within(range.end..len, range.start + s.len());
+        self.chars_buf[range.start..range.start + s.len()].copy_from_slice(s.as_bytes());
+        self.set_len(kept + s.len());
+        self.set_trailing_byte_to_null();
+        Ok(())
+    }
+
+    /// Read a `PascalString` from `reader`, which holds a length prefix followed by the string data, laid
+    /// out as described by `layout`.
+    ///
+    /// If the length prefix is larger than the capacity of the string, or the string data is not valid
+    /// utf8, then an error of kind `io::ErrorKind::InvalidData` is returned, which wraps a
+    /// `PascalStringCreateError`.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R, layout: Layout) -> io::Result<Self> {
+        let len = ::read_prefix::<L, R>(reader)?;
+        if len > N {
+            let error = PascalStringCreateError::InputTooLong { required: len, available: N };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        let mut bytes = [0u8; N];
+        reader.read_exact(&mut bytes[..len])?;
+        if layout == Layout::Record {
+            ::skip_bytes(reader, N - len)?;
+        }
+        PascalString::_from_utf8(&bytes[..len]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write this `PascalString` to `writer`, as a length prefix followed by the string data, laid out
+    /// as described by `layout`.
+    ///
+    /// Unlike `to_array()`, stale bytes past the end of the string are never written.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_to<W: Write>(&self, writer: &mut W, layout: Layout) -> io::Result<()> {
+        ::write_prefixed::<L, W>(writer, self.as_bytes(), N, layout)
+    }
+
+    /// Returns the maximum number of bytes which this `PascalString` can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the number of bytes used by the string is equal to its capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Get a byte in the string, without checking if the index is within the bounds of `len()`.
+    ///
+    /// This method cannot cause memory unsafety because `index` is bounds checked within the capacity of
+    /// the `PascalString`, which means that it cannot read uninitialised memory. However, it can give access
+    /// to stale bytes if `index` is greater than or equal to `self.len()`, and `self.is_full()` is `false`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `index` is larger than or equal to the capacity of the string.
+    #[inline]
+    pub fn get_unchecked(&self, index: usize) -> u8 {
+        self.chars_buf[index]
+    }
+
+    /// Get this string as a `CStr`.
+    ///
+    /// Returns `Err(InteriorNullError)` if the string contains any interior nulls. If this string is not
+    /// full, then the trailing null byte which is kept past the end of the string is used, so no allocation
+    /// is made. If the string is full, then a new `CString` will be allocated to hold the trailing null byte.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn as_cstr(&self) -> Result<Cow<'_, CStr>, InteriorNullError> {
+        if self.is_full() {
+            return self.deref().as_cstr();
+        }
+        let len = self.len();
+        let bytes = &self.chars_buf[..len + 1];
+        match bytes.iter().position(|&b| b == 0) {
+            Some(pos) if pos < len && pos != len - 1 => Err(InteriorNullError::new(pos)),
+            Some(pos) => Ok(Cow::Borrowed(CStr::from_bytes_with_nul(&bytes[..pos + 1]).unwrap())),
+            None => unreachable!("the byte after the end of a PascalString must be null")
+        }
+    }
+
+    /// Consumes this `PascalString`, and returns its inner state as a `[u8; M]`, where the first bytes
+    /// store the length prefix, and the rest store the utf8 encoded contents of the string. `M` must be equal
+    /// to the capacity of the string plus the width of the length prefix.
+    ///
+    /// Using an `M` which is not equal to `N + L::WIDTH` is a compile time error.
+    #[inline]
+    pub fn to_array<const M: usize>(self) -> [u8; M] {
+        let () = ::ArrayLenCheck::<L, N, M>::VALID;
+        let mut array = [0u8; M];
+        L::write_len(self.len, &mut array);
+        array[L::WIDTH..].copy_from_slice(&self.chars_buf);
+        array
+    }
+
+    /// Sets the byte beyond the end of `len` to `0`, if this `PascalString` isn't full.
+    ///
+    /// Used to ensure that `PascalString::as_cstr()` works correctly.
+    #[inline]
+    fn set_trailing_byte_to_null(&mut self) {
+        if !self.is_full() {
+            self.chars_buf[self.len()] = 0;
+        }
+    }
+
+    /// Sets the number of bytes used by the string, without touching the contents of the buffer.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+        self.len = L::from_usize(len);
+    }
+}
+
+impl<const N: usize> PascalString<N> {
+    /// Create a new `PascalString` from `s` in a constant expression.
+    ///
+    /// This is mostly useful for building `const` and `static` tables of strings. See also the `pstr!`
+    /// macro, which always evaluates this at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than the capacity of the string. When this is evaluated at compile time, the
+    /// panic is reported as a compile error.
+    pub const fn from_literal(s: &str) -> Self {
+        let bytes = s.as_bytes();
+        assert!(bytes.len() <= N, "the literal is longer than the capacity of the PascalString");
+        let mut pstring = PascalString::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            pstring.chars_buf[i] = bytes[i];
+            i += 1;
+        }
+        pstring.len = bytes.len() as u8;
+        pstring
+    }
 }
 
-impl fmt::Debug for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Debug for PascalString<N, L> {
     #[inline]
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         fmtr.debug_struct("PascalString")
@@ -109,44 +701,88 @@ impl fmt::Debug for PascalString {
     }
 }
 
-impl fmt::Display for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Display for PascalString<N, L> {
     #[inline]
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         fmtr.pad(self.as_str())
     }
 }
 
-impl Default for PascalString {
+impl<const N: usize, L: LengthPrefix> fmt::Write for PascalString<N, L> {
+    /// Fails if there is no room left in the string, in which case the string is
+    /// not modified.
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.try_push(ch).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Default for PascalString<N, L> {
     #[inline]
     fn default() -> Self {
-        PascalString {
-            len: 0,
-            chars_buf: [0u8; PASCAL_STRING_BUF_SIZE]
-        }
+        PascalString::new()
     }
 }
 
-impl Clone for PascalString {
+impl<const N: usize, L: LengthPrefix> Clone for PascalString<N, L> {
     #[inline]
     fn clone(&self) -> Self {
-        let mut clone = PascalString::default();
-        clone.len = self.len;
-        unsafe {
-            ptr::copy_nonoverlapping(&self.chars_buf, &mut clone.chars_buf, PASCAL_STRING_BUF_SIZE);
+        PascalString {
+            len: self.len,
+            chars_buf: self.chars_buf
         }
-        clone
     }
 }
 
-impl Hash for PascalString {
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+impl<const N: usize, L: LengthPrefix> AsciiExt for PascalString<N, L> {
+    type Owned = Self;
+
+    fn is_ascii(&self) -> bool {
+        self.as_str().is_ascii()
+    }
+
+    fn to_ascii_uppercase(&self) -> Self::Owned {
+        let mut upper = self.clone();
+        upper.make_ascii_uppercase();
+        upper
+    }
+
+    fn to_ascii_lowercase(&self) -> Self::Owned {
+        let mut lower = self.clone();
+        lower.make_ascii_lowercase();
+        lower
+    }
+
+    fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+
+    #[inline]
+    fn make_ascii_uppercase(&mut self) {
+        self.as_mut_str().make_ascii_uppercase()
+    }
+
+    #[inline]
+    fn make_ascii_lowercase(&mut self) {
+        self.as_mut_str().make_ascii_lowercase()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Hash for PascalString<N, L> {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_u8(self.len);
-        state.write(&self.chars_buf);
+        self.as_str().hash(state);
     }
 }
 
-impl<S: AsRef<str> + ?Sized> PartialEq<S> for PascalString {
+impl<S: AsRef<str> + ?Sized, const N: usize, L: LengthPrefix> PartialEq<S> for PascalString<N, L> {
     #[inline]
     fn eq(&self, other: &S) -> bool {
         let other = other.as_ref();
@@ -154,129 +790,397 @@ impl<S: AsRef<str> + ?Sized> PartialEq<S> for PascalString {
     }
 }
 
-impl Eq for PascalString {}
+impl<const N: usize, L: LengthPrefix> Eq for PascalString<N, L> {}
 
-impl<S: AsRef<str> + ?Sized> PartialOrd<S> for PascalString {
+impl<S: AsRef<str> + ?Sized, const N: usize, L: LengthPrefix> PartialOrd<S> for PascalString<N, L> {
     #[inline]
     fn partial_cmp(&self, other: &S) -> Option<Ordering> {
         let other = other.as_ref();
-        self.as_str().partial_cmp(&other)
+        self.as_str().partial_cmp(other)
     }
 }
 
-impl Ord for PascalString {
+impl<const N: usize, L: LengthPrefix> Ord for PascalString<N, L> {
     #[inline]
     fn cmp(&self, other: &Self) -> Ordering {
         self.as_str().cmp(other.as_str())
     }
 }
 
-impl Deref for PascalString {
+impl<const N: usize, L: LengthPrefix> Deref for PascalString<N, L> {
     type Target = PascalStr;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
-        unsafe { mem::transmute(&self.chars_buf[0..(self.len as usize)]) }
+        unsafe { mem::transmute(&self.chars_buf[0..L::to_usize(self.len)]) }
     }
 }
 
-impl DerefMut for PascalString {
+impl<const N: usize, L: LengthPrefix> DerefMut for PascalString<N, L> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { mem::transmute(&mut self.chars_buf[0..(self.len as usize)]) }
+        unsafe { mem::transmute(&mut self.chars_buf[0..L::to_usize(self.len)]) }
     }
 }
 
-impl AsRef<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<PascalStr> for PascalString<N, L> {
     #[inline]
     fn as_ref(&self) -> &PascalStr {
         self.deref()
     }
 }
 
-impl AsRef<str> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<str> for PascalString<N, L> {
     #[inline]
     fn as_ref(&self) -> &str {
         self.as_str()
     }
 }
 
-impl Borrow<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> Borrow<PascalStr> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &PascalStr {
         self.deref()
     }
 }
 
-impl BorrowMut<PascalStr> for PascalString {
+impl<const N: usize, L: LengthPrefix> BorrowMut<PascalStr> for PascalString<N, L> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut PascalStr {
         self.deref_mut()
     }
 }
 
-impl Borrow<str> for PascalString {
+impl<const N: usize, L: LengthPrefix> AsRef<[u8]> for PascalString<N, L> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> Borrow<str> for PascalString<N, L> {
     #[inline]
     fn borrow(&self) -> &str {
         self.as_str()
     }
 }
 
-impl BorrowMut<str> for PascalString {
+impl<const N: usize, L: LengthPrefix> BorrowMut<str> for PascalString<N, L> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut str {
         self.as_mut_str()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum PascalStringCreateError {
-    InputTooLong
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for String {
+    #[inline]
+    fn from(string: PascalString<N, L>) -> Self {
+        String::from(string.as_str())
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum PascalStringAppendError {
-    NoRoom,
-    EncodeError(EncodeUtf8Error)
+#[cfg(feature = "alloc")]
+impl<const N: usize, L: LengthPrefix> From<PascalString<N, L>> for Vec<u8> {
+    fn from(string: PascalString<N, L>) -> Self {
+        let mut v = Vec::with_capacity(string.len());
+        v.extend_from_slice(string.as_bytes());
+        v
+    }
 }
 
-impl fmt::Display for PascalStringAppendError {
+impl<const N: usize, L: LengthPrefix> From<ascii::PascalString<N, L>> for PascalString<N, L> {
+    /// Converts an ascii `PascalString` into a `utf8::PascalString` with the same capacity, length prefix and
+    /// contents. Ascii is valid utf8, so this cannot fail.
     #[inline]
-    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
-        let desc = self.description();
-        match *self {
-            PascalStringAppendError::NoRoom => fmtr.pad(desc),
-            PascalStringAppendError::EncodeError(ref e) => write!(fmtr, "{}: {:?}", desc, e)
+    fn from(string: ascii::PascalString<N, L>) -> Self {
+        let bytes = string.as_str().as_bytes();
+        let mut pstring = PascalString::new();
+        pstring.chars_buf[..bytes.len()].copy_from_slice(bytes);
+        pstring.set_len(bytes.len());
+        pstring.set_trailing_byte_to_null();
+        pstring
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> FromStr for PascalString<N, L> {
+    type Err = PascalStringCreateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PascalString::_from_str(s)
+    }
+}
+
+impl<const N: usize, L: LengthPrefix> FromIterator<char> for PascalString<N, L> {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut pstring = PascalString::new();
+        for ch in iter {
+            // It's probably kinder to drop characters which don't fit than panic in this method.
+            let _ = pstring.try_push(ch);
         }
+        pstring
     }
 }
 
-impl Error for PascalStringAppendError {
+impl<const N: usize, L: LengthPrefix> IntoIterator for PascalString<N, L> {
+    type Item = char;
+    type IntoIter = IntoChars<N, L>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoChars {
+            string: self,
+            index: 0
+        }
+    }
+}
+
+/// An iterator over the characters of a `PascalString`. Has ownership of the iterated `PascalString`.
+#[derive(Debug)]
+pub struct IntoChars<const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
+    string: PascalString<N, L>,
+    index: usize
+}
+
+impl<const N: usize, L: LengthPrefix> Iterator for IntoChars<N, L> {
+    type Item = char;
+    fn next(&mut self) -> Option<Self::Item> {
+        let ch = self.string[self.index..].chars().next()?;
+        self.index += ch.len_utf8();
+        Some(ch)
+    }
+
     #[inline]
-    fn description(&self) -> &str {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.string[self.index..].chars().size_hint()
+    }
+}
+
+/// A draining iterator over a range of characters in a `PascalString`.
+///
+/// This is created by `PascalString::drain()`.
+#[derive(Debug)]
+pub struct Drain<'a, const N: usize = PASCAL_STRING_BUF_SIZE, L: LengthPrefix = U8> {
+    string: &'a mut PascalString<N, L>,
+    /// The byte range which is removed when the iterator is dropped.
+    start: usize,
+    end: usize,
+    /// The byte range which has not been yielded yet.
+    front: usize,
+    back: usize
+}
+
+impl<'a, const N: usize, L: LengthPrefix> Drain<'a, N, L> {
+    /// Returns the characters which have not been yielded yet.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.string.as_str()[self.front..self.back]
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> Iterator for Drain<'a, N, L> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> DoubleEndedIterator for Drain<'a, N, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl<'a, const N: usize, L: LengthPrefix> Drop for Drain<'a, N, L> {
+    fn drop(&mut self) {
+        let len = self.string.len();
+        self.string.chars_buf.copy_within(self.end..len, self.start);
+        self.string.set_len(len - (self.end - self.start));
+        self.string.set_trailing_byte_to_null();
+    }
+}
+
+/// Adapts a `PascalString` to `fmt::Write` for `PascalString::format()`, keeping hold of the error which
+/// stopped the formatting.
+struct FormatWriter<const N: usize, L: LengthPrefix> {
+    string: PascalString<N, L>,
+    truncate: bool,
+    /// Whether some of the formatted text has been cut off, after which nothing more is stored.
+    truncated: bool,
+    /// The length of the formatted text so far, which can be longer than the string.
+    required: usize,
+    error: Option<PascalStringAppendError>
+}
+
+impl<const N: usize, L: LengthPrefix> fmt::Write for FormatWriter<N, L> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.required += s.len();
+        if self.truncate {
+            // A later, shorter fragment could still fit after a character which did not, so stop at the first
+            // cut to keep the string a prefix of the formatted text.
+            if !self.truncated {
+                self.truncated = !self.string.push_str_truncated(s);
+            }
+            return Ok(());
+        }
+        if self.required > N {
+            // Measure the rest of the text without storing it, so that the error reports its full length.
+            return Ok(());
+        }
+        self.string.try_push_str(s).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Indicates the range of errors which can occur from creating a new `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringCreateError {
+    /// The data provided to the constructor was larger than the `PascalString` could store.
+    InputTooLong {
+        /// The number of bytes in the data.
+        required: usize,
+        /// The capacity of the string.
+        available: usize
+    },
+    /// The data provided was not correctly encoded as utf8.
+    NotValidUtf8(Utf8Error)
+}
+
+impl PascalStringCreateError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringCreateError::InputTooLong { .. } => "the input data is longer than what a PascalString can store",
+            PascalStringCreateError::NotValidUtf8(_) => "the input data is not valid utf8"
+        }
+    }
+
+    /// Returns the number of bytes at the start of the input which could have been stored: the capacity of the
+    /// string if the input was too long, or the offset of the first invalid utf8 sequence.
+    ///
+    /// Note that when the input was too long, this may not lie on a character boundary.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
         match *self {
-            PascalStringAppendError::NoRoom => "there is no room for the string to be appended",
-            PascalStringAppendError::EncodeError(_) => "there was a problem encoding the character as utf8"
+            PascalStringCreateError::InputTooLong { available, .. } => available,
+            PascalStringCreateError::NotValidUtf8(ref e) => e.valid_up_to()
         }
     }
 
+    /// Returns the part of `input`, which must be the input that caused this error, from `valid_up_to()`
+    /// onwards. If `valid_up_to()` lies inside a character, the whole character is included.
     #[inline]
-    fn cause(&self) -> Option<&Error> {
-        // std::error::Error is not implemented for EncodeUtf8Error
-        /*
-        if let PascalStringAppendError::EncodeError(ref e) = *self {
+    pub fn unconsumed<'a, B: AsRef<[u8]> + ?Sized>(&self, input: &'a B) -> &'a [u8] {
+        unconsumed(input.as_ref(), self.valid_up_to())
+    }
+}
+
+impl fmt::Display for PascalStringCreateError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringCreateError::InputTooLong { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+            PascalStringCreateError::NotValidUtf8(ref e) => write!(fmtr, "{}: {}", self.message(), e)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringCreateError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        if let PascalStringCreateError::NotValidUtf8(ref e) = *self {
             Some(e)
         } else {
             None
         }
-        */
-        None
     }
 }
 
-impl From<EncodeUtf8Error> for PascalStringAppendError {
+impl From<Utf8Error> for PascalStringCreateError {
+    #[inline]
+    fn from(e: Utf8Error) -> Self {
+        PascalStringCreateError::NotValidUtf8(e)
+    }
+}
+
+/// Indicates the range of errors which can occur from appending string data to a `PascalString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PascalStringAppendError {
+    /// There is no room to store the appended data.
+    NoRoom {
+        /// The number of bytes needed to store the appended data.
+        required: usize,
+        /// The number of bytes which were left in the string.
+        available: usize
+    }
+}
+
+impl PascalStringAppendError {
+    fn message(&self) -> &'static str {
+        match *self {
+            PascalStringAppendError::NoRoom { .. } => "there is no room for the string to be appended"
+        }
+    }
+
+    /// Returns the number of bytes at the start of the appended data which could have been stored, which is
+    /// the room which was left in the string.
+    ///
+    /// Note that this may not lie on a character boundary.
     #[inline]
-    fn from(e: EncodeUtf8Error) -> Self {
-        PascalStringAppendError::EncodeError(e)
+    pub fn valid_up_to(&self) -> usize {
+        match *self {
+            PascalStringAppendError::NoRoom { available, .. } => available
+        }
+    }
+
+    /// Returns the part of `input`, which must be the appended data that caused this error, from
+    /// `valid_up_to()` onwards. If `valid_up_to()` lies inside a character, the whole character is included.
+    #[inline]
+    pub fn unconsumed<'a, B: AsRef<[u8]> + ?Sized>(&self, input: &'a B) -> &'a [u8] {
+        unconsumed(input.as_ref(), self.valid_up_to())
+    }
+}
+
+impl fmt::Display for PascalStringAppendError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PascalStringAppendError::NoRoom { required, available } => {
+                write!(fmtr, "{} ({} bytes required, {} available)", self.message(), required, available)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PascalStringAppendError {
+    #[inline]
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
+/// Returns `input` from `index` onwards, moving `index` back to the start of the character it lies in.
+fn unconsumed(input: &[u8], index: usize) -> &[u8] {
+    let mut index = cmp::min(index, input.len());
+    // Continuation bytes of a multi-byte character are of the form `0b10xx_xxxx`.
+    while index > 0 && index < input.len() && input[index] & 0xc0 == 0x80 {
+        index -= 1;
     }
+    &input[index..]
 }
@@ -0,0 +1,151 @@
+use core::cmp;
+use ::utf8::PascalStr;
+
+/// A pattern which can be searched for in a `utf8::PascalStr`.
+///
+/// This is implemented for the same types as the standard library's string patterns:
+///
+/// * Single characters: `char`.
+/// * Sets of characters, where any character in the set matches: `&[char]` and `&[char; N]`.
+/// * Substrings: `&str` and `&PascalStr`.
+/// * Predicates: `FnMut(char) -> bool`.
+pub trait Pattern {
+    /// If this pattern matches `haystack` starting at the byte `index`, returns the number of bytes in the
+    /// match.
+    ///
+    /// `index` is always on a character boundary, and may be equal to the length of `haystack`, in which
+    /// case only an empty match is possible.
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize>;
+}
+
+impl Pattern for char {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        match haystack[index..].chars().next() {
+            Some(ch) if ch == *self => Some(ch.len_utf8()),
+            _ => None
+        }
+    }
+}
+
+impl Pattern for &[char] {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        match haystack[index..].chars().next() {
+            Some(ch) if self.contains(&ch) => Some(ch.len_utf8()),
+            _ => None
+        }
+    }
+}
+
+impl<const M: usize> Pattern for &[char; M] {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        (&self[..]).match_at(haystack, index)
+    }
+}
+
+impl Pattern for &str {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        if haystack[index..].starts_with(*self) {
+            Some(self.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl Pattern for &PascalStr {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        self.as_str().match_at(haystack, index)
+    }
+}
+
+impl<F: FnMut(char) -> bool> Pattern for F {
+    #[inline]
+    fn match_at(&mut self, haystack: &str, index: usize) -> Option<usize> {
+        match haystack[index..].chars().next() {
+            Some(ch) if self(ch) => Some(ch.len_utf8()),
+            _ => None
+        }
+    }
+}
+
+/// Finds the non-overlapping matches of a pattern in a string, from either end.
+///
+/// Only character boundaries are tried, so every match can be used to slice the string.
+#[derive(Clone, Debug)]
+pub(crate) struct Searcher<'a, P> {
+    haystack: &'a str,
+    pattern: P,
+    /// The next index to try from the front.
+    position: usize,
+    /// Matches found from the back must end at or before this index.
+    back: usize,
+    /// The next index to try from the back, or `None` if the start of the string has been passed.
+    back_position: Option<usize>
+}
+
+impl<'a, P: Pattern> Searcher<'a, P> {
+    #[inline]
+    pub(crate) fn new(haystack: &'a str, pattern: P) -> Self {
+        Searcher {
+            haystack: haystack,
+            pattern: pattern,
+            position: 0,
+            back: haystack.len(),
+            back_position: Some(haystack.len())
+        }
+    }
+
+    #[inline]
+    pub(crate) fn haystack(&self) -> &'a str {
+        self.haystack
+    }
+
+    /// The index of the character boundary after `index`.
+    #[inline]
+    fn next_boundary(&self, index: usize) -> usize {
+        index + self.haystack[index..].chars().next().map_or(1, char::len_utf8)
+    }
+
+    /// The index of the character boundary before `index`, if there is one.
+    #[inline]
+    fn prev_boundary(&self, index: usize) -> Option<usize> {
+        self.haystack[..index].char_indices().next_back().map(|(i, _)| i)
+    }
+
+    /// Find the next match from the front, returning the index and length in bytes of the match.
+    pub(crate) fn next_match(&mut self) -> Option<(usize, usize)> {
+        while self.position <= self.haystack.len() {
+            let start = self.position;
+            if let Some(len) = self.pattern.match_at(self.haystack, start) {
+                // An empty match would otherwise be found at the same index forever.
+                self.position = cmp::max(start + len, self.next_boundary(start));
+                return Some((start, len));
+            }
+            self.position = self.next_boundary(start);
+        }
+        None
+    }
+
+    /// Find the next match from the back, returning the index and length in bytes of the match.
+    pub(crate) fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        while let Some(start) = self.back_position {
+            self.back_position = self.prev_boundary(start);
+            match self.pattern.match_at(self.haystack, start) {
+                Some(len) if start + len <= self.back => {
+                    self.back = start;
+                    if len > 0 {
+                        self.back_position = Some(start);
+                    }
+                    return Some((start, len));
+                }
+                _ => ()
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,3 @@
+serde_impls!(utf8, from_utf8, "Fails with the message of a `PascalStringCreateError` if the input is longer than \
+                                the capacity of the string, or is not valid utf8.",
+             "a utf8 string of at most {} bytes");
@@ -0,0 +1,2 @@
+split_iterators!(utf8, str, char, is_whitespace,
+                 "An iterator over the non-empty parts of a `PascalStr` separated by unicode whitespace.");